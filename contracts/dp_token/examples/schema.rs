@@ -0,0 +1,18 @@
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cw20::Cw20ExecuteMsg as ExecuteMsg;
+use cw20_base::msg::{InstantiateMsg, QueryMsg};
+use pylon_core::dp_token_msg::MigrateMsg;
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+}
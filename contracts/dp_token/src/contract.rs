@@ -0,0 +1,121 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{
+    to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg as ExecuteMsg;
+use cw20_base::msg::{InstantiateMsg, QueryMsg};
+use cw20_base::state::{MinterData, TOKEN_INFO};
+use pylon_core::dp_token_msg::MigrateMsg;
+use pylon_core::pool_msg::ExecuteMsg as PoolExecuteMsg;
+
+use crate::error::ContractError;
+
+const CONTRACT_NAME: &str = "crates.io:pylon-core-dp-token";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// plain cw20 DP tokens created by earlier pools
+const CW20_BASE_NAME: &str = "crates.io:cw20-base";
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let resp = cw20_base::contract::instantiate(deps.branch(), env, info, msg)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(resp)
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // balances moved without the pool, it re-reads them once cw20-base is done
+    let holders = match &msg {
+        ExecuteMsg::Transfer { recipient, .. } => vec![info.sender.to_string(), recipient.clone()],
+        ExecuteMsg::Send { contract, .. } => vec![info.sender.to_string(), contract.clone()],
+        ExecuteMsg::TransferFrom {
+            owner, recipient, ..
+        } => vec![owner.clone(), recipient.clone()],
+        ExecuteMsg::SendFrom {
+            owner, contract, ..
+        } => vec![owner.clone(), contract.clone()],
+        ExecuteMsg::Burn { .. } => vec![info.sender.to_string()],
+        ExecuteMsg::BurnFrom { owner, .. } => vec![owner.clone()],
+        _ => vec![],
+    };
+
+    let resp = cw20_base::contract::execute(deps.branch(), env, info, msg)?;
+    match sync_msg(deps.as_ref(), holders)? {
+        Some(msg) => Ok(resp.add_message(msg)),
+        None => Ok(resp),
+    }
+}
+
+/// asks the minting pool to sync the DP balances of `holders`, leaving out the pool itself
+fn sync_msg(deps: Deps, holders: Vec<String>) -> StdResult<Option<CosmosMsg>> {
+    let minter = match TOKEN_INFO.load(deps.storage)?.mint {
+        Some(mint) => mint.minter,
+        None => return Ok(None),
+    };
+
+    let mut owners: Vec<String> = vec![];
+    for holder in holders {
+        if holder != minter.as_str() && !owners.contains(&holder) {
+            owners.push(holder);
+        }
+    }
+    if owners.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: minter.to_string(),
+        msg: to_binary(&PoolExecuteMsg::SyncBalances { owners })?,
+        funds: vec![],
+    })))
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    cw20_base::contract::query(deps, env, msg)
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = cw2::get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME && stored.contract != CW20_BASE_NAME {
+        return Err(ContractError::MigrationContractMismatch {
+            expected: CONTRACT_NAME.to_string(),
+            actual: stored.contract,
+        });
+    }
+
+    if let Some(minter) = msg.minter {
+        let minter = deps.api.addr_validate(minter.as_str())?;
+        TOKEN_INFO.update(deps.storage, |mut info| -> StdResult<_> {
+            info.mint = Some(MinterData {
+                minter,
+                cap: info.get_cap(),
+            });
+            Ok(info)
+        })?;
+    }
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION))
+}
@@ -0,0 +1,6 @@
+pub mod contract;
+
+mod error;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,197 @@
+use crate::contract;
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, OwnedDeps, Uint128, WasmMsg};
+use cw20::{Cw20Coin, Cw20ExecuteMsg, MinterResponse};
+use cw20_base::msg::InstantiateMsg;
+use cw20_base::state::TOKEN_INFO;
+use pylon_core::dp_token_msg::MigrateMsg;
+use pylon_core::pool_msg::ExecuteMsg as PoolExecuteMsg;
+
+const POOL: &str = "pool";
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(POOL, &[]),
+        InstantiateMsg {
+            name: "Pylon DP Token".to_string(),
+            symbol: "DPT".to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: "user".to_string(),
+                amount: Uint128::from(100u64),
+            }],
+            mint: Some(MinterResponse {
+                minter: POOL.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        },
+    )
+    .unwrap();
+
+    deps
+}
+
+fn sync_balances(owners: &[&str]) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: POOL.to_string(),
+        msg: to_binary(&PoolExecuteMsg::SyncBalances {
+            owners: owners.iter().map(|owner| owner.to_string()).collect(),
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+#[test]
+fn transfer_syncs_both_holders() {
+    let mut deps = setup();
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        Cw20ExecuteMsg::Transfer {
+            recipient: "other".to_string(),
+            amount: Uint128::from(40u64),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        resp.messages
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect::<Vec<_>>(),
+        vec![sync_balances(&["user", "other"])]
+    );
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other", &[]),
+        Cw20ExecuteMsg::Burn {
+            amount: Uint128::from(10u64),
+        },
+    )
+    .unwrap();
+    assert_eq!(resp.messages[0].msg, sync_balances(&["other"]));
+}
+
+#[test]
+fn pool_is_left_out_of_syncs() {
+    let mut deps = setup();
+
+    // the receive hook goes first, the pool syncs the sender itself as well
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        Cw20ExecuteMsg::Send {
+            contract: POOL.to_string(),
+            amount: Uint128::from(40u64),
+            msg: Binary::default(),
+        },
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 2);
+    assert_eq!(resp.messages[1].msg, sync_balances(&["user"]));
+
+    // burning redeemed DP or minting is tracked by the pool already
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(POOL, &[]),
+        Cw20ExecuteMsg::Burn {
+            amount: Uint128::from(40u64),
+        },
+    )
+    .unwrap();
+    assert!(resp.messages.is_empty());
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(POOL, &[]),
+        Cw20ExecuteMsg::Mint {
+            recipient: "user".to_string(),
+            amount: Uint128::from(40u64),
+        },
+    )
+    .unwrap();
+    assert!(resp.messages.is_empty());
+}
+
+#[test]
+fn migrate_hands_over_minter() {
+    let mut deps = mock_dependencies(&[]);
+    cw20_base::contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(POOL, &[]),
+        InstantiateMsg {
+            name: "Pylon DP Token".to_string(),
+            symbol: "DPT".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: POOL.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        },
+    )
+    .unwrap();
+
+    contract::migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg {
+            minter: Some("new-pool".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        TOKEN_INFO
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .mint
+            .unwrap()
+            .minter,
+        Addr::unchecked("new-pool")
+    );
+    assert_eq!(
+        cw2::get_contract_version(deps.as_ref().storage)
+            .unwrap()
+            .contract,
+        "crates.io:pylon-core-dp-token"
+    );
+
+    // keeps the minter when none is given
+    contract::migrate(deps.as_mut(), mock_env(), MigrateMsg { minter: None }).unwrap();
+    assert_eq!(
+        TOKEN_INFO
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .mint
+            .unwrap()
+            .minter,
+        Addr::unchecked("new-pool")
+    );
+
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:other", "1.0.0").unwrap();
+    let err =
+        contract::migrate(deps.as_mut(), mock_env(), MigrateMsg { minter: None }).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MigrationContractMismatch {
+            expected: "crates.io:pylon-core-dp-token".to_string(),
+            actual: "crates.io:other".to_string(),
+        }
+    );
+}
@@ -0,0 +1,44 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read};
+
+use crate::error::ContractError;
+
+pub static CONFIG_KEY: &[u8] = b"config";
+
+/// Hard upper bound of the protocol fee rate, same as the pool's (50%)
+pub fn max_fee_rate() -> Decimal256 {
+    Decimal256::percent(50)
+}
+
+pub fn validate_fee_rate(fee_rate: Decimal256) -> Result<(), ContractError> {
+    if fee_rate > max_fee_rate() {
+        return Err(ContractError::InvalidFeeRate {
+            max: max_fee_rate(),
+            actual: fee_rate,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub owner: CanonicalAddr,
+    pub pool_code_id: u64,
+    pub dp_code_id: u64,
+    // defaults of the pools created from now on
+    pub fee_collector: CanonicalAddr,
+    pub fee_rate: Decimal256,
+}
+
+pub fn store(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
+    singleton(storage, CONFIG_KEY).save(data)
+}
+
+pub fn read(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, CONFIG_KEY).load()
+}
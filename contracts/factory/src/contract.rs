@@ -0,0 +1,163 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response};
+use protobuf::Message;
+use pylon_core::address;
+use pylon_core::factory_msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use semver::Version;
+
+use crate::config;
+use crate::error::ContractError;
+use crate::handler::core as CoreHandler;
+use crate::handler::query as QueryHandler;
+use pylon_core::response::MsgInstantiateContractResponse;
+
+const CONTRACT_NAME: &str = "crates.io:pylon-core-factory";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub(crate) const INSTANTIATE_REPLY_ID: u64 = 1;
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    config::validate_fee_rate(msg.fee_rate)?;
+
+    config::store(
+        deps.storage,
+        &config::Config {
+            owner: address::canonicalize(deps.api, info.sender.as_str())?,
+            pool_code_id: msg.pool_code_id,
+            dp_code_id: msg.dp_code_id,
+            fee_collector: address::canonicalize(deps.api, msg.fee_collector.as_str())?,
+            fee_rate: msg.fee_rate,
+        },
+    )?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Configure {
+            pool_code_id,
+            dp_code_id,
+            fee_collector,
+            fee_rate,
+        } => CoreHandler::configure(
+            deps,
+            env,
+            info,
+            pool_code_id,
+            dp_code_id,
+            fee_collector,
+            fee_rate,
+        ),
+        ExecuteMsg::CreatePool {
+            pool_name,
+            beneficiary,
+            moneymarket,
+            moneymarket_kind,
+            fee_collector,
+            fee_rate,
+        } => CoreHandler::create_pool(
+            deps,
+            env,
+            info,
+            pool_name,
+            beneficiary,
+            moneymarket,
+            moneymarket_kind,
+            fee_collector,
+            fee_rate,
+        ),
+    }
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_REPLY_ID => {
+            // get new pool's contract address
+            let id = msg.id;
+            let malformed = |reason: &str| ContractError::MalformedReply {
+                id,
+                reason: reason.to_string(),
+            };
+            let data = msg
+                .result
+                .into_result()
+                .map_err(|err| malformed(err.as_str()))?
+                .data
+                .ok_or_else(|| malformed("missing instantiate response data"))?;
+            let res: MsgInstantiateContractResponse = Message::parse_from_bytes(data.as_slice())
+                .map_err(|_| malformed("failed to parse MsgInstantiateContractResponse"))?;
+            let pool_addr = address::validate(deps.api, res.get_contract_address())?;
+
+            CoreHandler::register_pool(deps, env, pool_addr)
+        }
+        _ => Err(ContractError::InvalidReplyId { id: msg.id }),
+    }
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Config {} => QueryHandler::config(deps, env),
+        QueryMsg::Pools { start_after, limit } => {
+            QueryHandler::pools(deps, env, start_after, limit)
+        }
+        QueryMsg::PoolByName { name } => QueryHandler::pool_by_name(deps, env, name),
+        QueryMsg::PoolByDpToken { dp_token } => QueryHandler::pool_by_dp_token(deps, env, dp_token),
+        QueryMsg::PoolsByBeneficiary {
+            beneficiary,
+            start_after,
+            limit,
+        } => QueryHandler::pools_by_beneficiary(deps, env, beneficiary, start_after, limit),
+    }
+}
+
+#[allow(dead_code)]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = cw2::get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::MigrationContractMismatch {
+            expected: CONTRACT_NAME.to_string(),
+            actual: stored.contract,
+        });
+    }
+    if parse_version(&stored.version)? > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::MigrationDowngrade {
+            stored: stored.version,
+            current: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION))
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidVersion {
+        version: version.to_string(),
+    })
+}
@@ -0,0 +1,60 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::StdError;
+use pylon_core::address::InvalidAddress;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error(
+        "Core/Factory: Unauthorized (action: {action:?}, expected: {expected:?}, actual: {actual:?})"
+    )]
+    Unauthorized {
+        action: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Core/Factory: Cannot migrate from contract {actual:?} (expected: {expected:?})")]
+    MigrationContractMismatch { expected: String, actual: String },
+
+    #[error("Core/Factory: Cannot downgrade contract (stored: {stored:?}, current: {current:?})")]
+    MigrationDowngrade { stored: String, current: String },
+
+    #[error("Core/Factory: Invalid contract version {version:?}")]
+    InvalidVersion { version: String },
+
+    #[error("Core/Factory: Invalid reply ID (ID: {id:?}")]
+    InvalidReplyId { id: u64 },
+
+    #[error("Core/Factory: Malformed reply (ID: {id}, reason: {reason})")]
+    MalformedReply { id: u64, reason: String },
+
+    #[error("Core/Factory: Invalid address {address:?}")]
+    InvalidAddress { address: String },
+
+    #[error("Core/Factory: Invalid fee rate (max: {max}, actual: {actual})")]
+    InvalidFeeRate { max: Decimal256, actual: Decimal256 },
+
+    #[error("Core/Factory: Pool name must not be empty")]
+    EmptyPoolName {},
+
+    #[error("Core/Factory: Pool {name:?} already exists")]
+    PoolAlreadyExists { name: String },
+
+    #[error("Core/Factory: Pool not found")]
+    PoolNotFound {},
+
+    #[error("Core/Factory: No pool is waiting for registration")]
+    NoPendingPool {},
+}
+
+impl From<InvalidAddress> for ContractError {
+    fn from(err: InvalidAddress) -> Self {
+        ContractError::InvalidAddress {
+            address: err.address,
+        }
+    }
+}
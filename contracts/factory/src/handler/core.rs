@@ -0,0 +1,172 @@
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::*;
+use pylon_core::address;
+use pylon_core::pool_msg::{
+    ExecuteMsg as PoolExecuteMsg, InstantiateMsg as PoolInstantiateMsg, MoneyMarketKind,
+    QueryMsg as PoolQueryMsg,
+};
+use pylon_core::pool_resp::ConfigResponse as PoolConfigResponse;
+
+use crate::config;
+use crate::contract;
+use crate::error::ContractError;
+use crate::registry;
+
+fn assert_owner(
+    deps: Deps,
+    config: &config::Config,
+    action: &str,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    if config.owner != address::canonicalize(deps.api, sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: action.to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: sender.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn configure(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pool_code_id: Option<u64>,
+    dp_code_id: Option<u64>,
+    fee_collector: Option<String>,
+    fee_rate: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let mut config = config::read(deps.storage)?;
+    assert_owner(deps.as_ref(), &config, "configure", &info.sender)?;
+
+    if let Some(pool_code_id) = pool_code_id {
+        config.pool_code_id = pool_code_id;
+    }
+    if let Some(dp_code_id) = dp_code_id {
+        config.dp_code_id = dp_code_id;
+    }
+    if let Some(fee_collector) = fee_collector {
+        config.fee_collector = address::canonicalize(deps.api, fee_collector.as_str())?;
+    }
+    if let Some(fee_rate) = fee_rate {
+        config::validate_fee_rate(fee_rate)?;
+        config.fee_rate = fee_rate;
+    }
+
+    config::store(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "configure")
+        .add_attribute("sender", info.sender.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pool(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    pool_name: String,
+    beneficiary: String,
+    moneymarket: String,
+    moneymarket_kind: MoneyMarketKind,
+    fee_collector: Option<String>,
+    fee_rate: Option<Decimal256>,
+) -> Result<Response, ContractError> {
+    let config = config::read(deps.storage)?;
+    assert_owner(deps.as_ref(), &config, "create_pool", &info.sender)?;
+
+    if pool_name.is_empty() {
+        return Err(ContractError::EmptyPoolName {});
+    }
+    if registry::id_by_name(deps.storage, &pool_name)?.is_some() {
+        return Err(ContractError::PoolAlreadyExists { name: pool_name });
+    }
+    let fee_rate = fee_rate.unwrap_or(config.fee_rate);
+    config::validate_fee_rate(fee_rate)?;
+    let fee_collector = match fee_collector {
+        Some(fee_collector) => address::validate(deps.api, fee_collector.as_str())?,
+        None => address::humanize(deps.api, &config.fee_collector)?,
+    };
+    let beneficiary = address::validate(deps.api, beneficiary.as_str())?;
+    let moneymarket = address::validate(deps.api, moneymarket.as_str())?;
+
+    registry::store_pending(
+        deps.storage,
+        &registry::PendingPool {
+            name: pool_name.clone(),
+            beneficiary: address::canonicalize(deps.api, beneficiary.as_str())?,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(address::humanize(deps.api, &config.owner)?.to_string()),
+                code_id: config.pool_code_id,
+                funds: vec![],
+                label: format!("Pylon Pool - {}", pool_name),
+                msg: to_binary(&PoolInstantiateMsg {
+                    pool_name: pool_name.clone(),
+                    beneficiary: beneficiary.to_string(),
+                    fee_collector: fee_collector.to_string(),
+                    fee_rate,
+                    moneymarket: moneymarket.to_string(),
+                    moneymarket_kind,
+                    dp_code_id: Some(config.dp_code_id),
+                    dp_token: None,
+                    dp_token_name: None,
+                    dp_token_symbol: None,
+                    dp_token_marketing: None,
+                    dp_token_label: None,
+                    dp_token_admin: None,
+                })?,
+            },
+            contract::INSTANTIATE_REPLY_ID,
+        ))
+        .add_attribute("action", "create_pool")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("pool_name", pool_name))
+}
+
+/// registers the pool instantiated by `create_pool` and hands its ownership to the factory owner
+pub fn register_pool(deps: DepsMut, env: Env, pool: Addr) -> Result<Response, ContractError> {
+    let config = config::read(deps.storage)?;
+    let pending = registry::read_pending(deps.storage)?.ok_or(ContractError::NoPendingPool {})?;
+    registry::remove_pending(deps.storage);
+
+    // the pool registered its DP token in its own instantiate reply, which ran first
+    let pool_config: PoolConfigResponse = deps
+        .querier
+        .query_wasm_smart(pool.to_string(), &PoolQueryMsg::Config {})?;
+
+    let id = registry::next_id(deps.storage)?;
+    registry::register(
+        deps.storage,
+        &registry::Pool {
+            id,
+            address: address::canonicalize(deps.api, pool.as_str())?,
+            name: pending.name,
+            dp_token: address::canonicalize(deps.api, pool_config.dp_token.as_str())?,
+            beneficiary: pending.beneficiary,
+            created_at: env.block.time.seconds(),
+        },
+    )?;
+
+    let owner = address::humanize(deps.api, &config.owner)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: pool.to_string(),
+            msg: to_binary(&PoolExecuteMsg::TransferOwnership {
+                new_owner: owner.to_string(),
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "register_pool")
+        .add_attribute("pool_id", id.to_string())
+        .add_attribute("pool", pool.to_string())
+        .add_attribute("dp_token", pool_config.dp_token)
+        .add_attribute("pending_owner", owner.to_string()))
+}
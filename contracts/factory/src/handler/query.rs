@@ -0,0 +1,86 @@
+use cosmwasm_std::*;
+use pylon_core::address;
+use pylon_core::factory_resp as resp;
+
+use crate::config;
+use crate::error::ContractError;
+use crate::registry;
+
+pub fn config(deps: Deps, _env: Env) -> Result<Binary, ContractError> {
+    let config = config::read(deps.storage)?;
+
+    Ok(to_binary(&resp::ConfigResponse {
+        owner: address::humanize(deps.api, &config.owner)?.to_string(),
+        pool_code_id: config.pool_code_id,
+        dp_code_id: config.dp_code_id,
+        fee_collector: address::humanize(deps.api, &config.fee_collector)?.to_string(),
+        fee_rate: config.fee_rate,
+    })?)
+}
+
+fn to_response(deps: Deps, pool: registry::Pool) -> Result<resp::PoolResponse, ContractError> {
+    Ok(resp::PoolResponse {
+        id: pool.id,
+        address: address::humanize(deps.api, &pool.address)?.to_string(),
+        name: pool.name,
+        dp_token: address::humanize(deps.api, &pool.dp_token)?.to_string(),
+        beneficiary: address::humanize(deps.api, &pool.beneficiary)?.to_string(),
+        created_at: pool.created_at,
+    })
+}
+
+fn to_pools_response(
+    deps: Deps,
+    pools: Vec<registry::Pool>,
+) -> Result<resp::PoolsResponse, ContractError> {
+    Ok(resp::PoolsResponse {
+        pools: pools
+            .into_iter()
+            .map(|pool| to_response(deps, pool))
+            .collect::<Result<Vec<resp::PoolResponse>, ContractError>>()?,
+    })
+}
+
+pub fn pools(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let pools = registry::pools(deps.storage, start_after, limit)?;
+
+    Ok(to_binary(&to_pools_response(deps, pools)?)?)
+}
+
+pub fn pool_by_name(deps: Deps, _env: Env, name: String) -> Result<Binary, ContractError> {
+    let id = registry::id_by_name(deps.storage, &name)?.ok_or(ContractError::PoolNotFound {})?;
+
+    Ok(to_binary(&to_response(
+        deps,
+        registry::read(deps.storage, id)?,
+    )?)?)
+}
+
+pub fn pool_by_dp_token(deps: Deps, _env: Env, dp_token: String) -> Result<Binary, ContractError> {
+    let dp_token = address::canonicalize(deps.api, dp_token.as_str())?;
+    let id =
+        registry::id_by_dp_token(deps.storage, &dp_token)?.ok_or(ContractError::PoolNotFound {})?;
+
+    Ok(to_binary(&to_response(
+        deps,
+        registry::read(deps.storage, id)?,
+    )?)?)
+}
+
+pub fn pools_by_beneficiary(
+    deps: Deps,
+    _env: Env,
+    beneficiary: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let beneficiary = address::canonicalize(deps.api, beneficiary.as_str())?;
+    let pools = registry::pools_of(deps.storage, &beneficiary, start_after, limit)?;
+
+    Ok(to_binary(&to_pools_response(deps, pools)?)?)
+}
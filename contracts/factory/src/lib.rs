@@ -0,0 +1,9 @@
+pub mod config;
+pub mod contract;
+
+mod error;
+mod handler;
+mod registry;
+
+#[cfg(test)]
+mod test;
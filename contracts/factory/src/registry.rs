@@ -0,0 +1,121 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_storage::{
+    bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket, ReadonlySingleton,
+    Singleton,
+};
+use pylon_utils::range::calc_range_start;
+
+pub static PREFIX_POOL: &[u8] = b"pool";
+pub static PREFIX_POOL_BY_NAME: &[u8] = b"pool_by_name";
+pub static PREFIX_POOL_BY_DP_TOKEN: &[u8] = b"pool_by_dp_token";
+pub static PREFIX_POOL_BY_BENEFICIARY: &[u8] = b"pool_by_beneficiary";
+pub static PENDING_POOL_KEY: &[u8] = b"pending_pool";
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pool {
+    pub id: u64,
+    pub address: CanonicalAddr,
+    pub name: String,
+    pub dp_token: CanonicalAddr,
+    pub beneficiary: CanonicalAddr, // as given at creation, never resynced with the pool
+    pub created_at: u64,
+}
+
+/// Pool being instantiated, registered by the instantiate reply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingPool {
+    pub name: String,
+    pub beneficiary: CanonicalAddr,
+}
+
+pub fn store_pending(storage: &mut dyn Storage, pending: &PendingPool) -> StdResult<()> {
+    singleton(storage, PENDING_POOL_KEY).save(pending)
+}
+
+pub fn read_pending(storage: &dyn Storage) -> StdResult<Option<PendingPool>> {
+    let pending: ReadonlySingleton<PendingPool> = singleton_read(storage, PENDING_POOL_KEY);
+    pending.may_load()
+}
+
+pub fn remove_pending(storage: &mut dyn Storage) {
+    let mut pending: Singleton<PendingPool> = singleton(storage, PENDING_POOL_KEY);
+    pending.remove()
+}
+
+/// id the next registered pool will get, starting from 1
+pub fn next_id(storage: &dyn Storage) -> StdResult<u64> {
+    let last = bucket_read::<Pool>(storage, PREFIX_POOL)
+        .range(None, None, Order::Descending)
+        .next()
+        .transpose()?;
+
+    Ok(last.map(|(_, pool)| pool.id + 1).unwrap_or(1))
+}
+
+fn beneficiary_namespace(beneficiary: &CanonicalAddr) -> [&[u8]; 2] {
+    [PREFIX_POOL_BY_BENEFICIARY, beneficiary.as_slice()]
+}
+
+/// stores `pool` along with its name, DP token and beneficiary indexes
+pub fn register(storage: &mut dyn Storage, pool: &Pool) -> StdResult<()> {
+    let id = pool.id.to_be_bytes();
+    bucket(storage, PREFIX_POOL).save(&id, pool)?;
+    bucket(storage, PREFIX_POOL_BY_NAME).save(pool.name.as_bytes(), &pool.id)?;
+    bucket(storage, PREFIX_POOL_BY_DP_TOKEN).save(pool.dp_token.as_slice(), &pool.id)?;
+    Bucket::multilevel(storage, &beneficiary_namespace(&pool.beneficiary)).save(&id, &pool.id)?;
+
+    Ok(())
+}
+
+pub fn read(storage: &dyn Storage, id: u64) -> StdResult<Pool> {
+    bucket_read(storage, PREFIX_POOL).load(&id.to_be_bytes())
+}
+
+pub fn id_by_name(storage: &dyn Storage, name: &str) -> StdResult<Option<u64>> {
+    bucket_read(storage, PREFIX_POOL_BY_NAME).may_load(name.as_bytes())
+}
+
+pub fn id_by_dp_token(storage: &dyn Storage, dp_token: &CanonicalAddr) -> StdResult<Option<u64>> {
+    bucket_read(storage, PREFIX_POOL_BY_DP_TOKEN).may_load(dp_token.as_slice())
+}
+
+/// pools in ascending order of id
+pub fn pools(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Pool>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    bucket_read(storage, PREFIX_POOL)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, pool)| pool))
+        .collect()
+}
+
+/// pools created for `beneficiary` in ascending order of id.
+/// the index is written once at creation, so a pool whose beneficiary was reconfigured
+/// stays listed under the original one
+pub fn pools_of(
+    storage: &dyn Storage,
+    beneficiary: &CanonicalAddr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Pool>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    ReadonlyBucket::<u64>::multilevel(storage, &beneficiary_namespace(beneficiary))
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| read(storage, item?.1))
+        .collect()
+}
@@ -0,0 +1,375 @@
+use crate::contract;
+use crate::error::ContractError;
+use cosmwasm_bignumber::Decimal256;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, ContractResult, CosmosMsg, OwnedDeps, Reply, ReplyOn,
+    SubMsgExecutionResponse, WasmMsg,
+};
+use protobuf::Message;
+use pylon_core::factory_msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use pylon_core::factory_resp::{ConfigResponse, PoolResponse, PoolsResponse};
+use pylon_core::pool_msg::{
+    ExecuteMsg as PoolExecuteMsg, InstantiateMsg as PoolInstantiateMsg, MoneyMarketKind,
+    RedemptionPolicy,
+};
+use pylon_core::pool_resp::ConfigResponse as PoolConfigResponse;
+use pylon_core::response::MsgInstantiateContractResponse;
+use pylon_testing::mock_querier::{mock_dependencies, CustomMockWasmQuerier};
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, CustomMockWasmQuerier>;
+
+/// pool `pool-{n}` registered DP token `dp-token-{n}`
+fn mock_pool(deps: &mut MockDeps, pool: &str) {
+    let dp_token = pool.replace("pool-", "dp-token-");
+    deps.querier.register_wasm_smart_query_handler(
+        pool.to_string(),
+        Box::new(move |_| {
+            to_binary(&PoolConfigResponse {
+                owner: "factory".to_string(),
+                pending_owner: None,
+                pauser: None,
+                beneficiary: "".to_string(),
+                beneficiaries: vec![],
+                fee_collector: "".to_string(),
+                fee_rate: Decimal256::zero(),
+                keeper: None,
+                moneymarket: "".to_string(),
+                moneymarket_kind: MoneyMarketKind::Anchor,
+                stable_denom: "uusd".to_string(),
+                anchor_token: "".to_string(),
+                dp_token: dp_token.clone(),
+                deposit_cap: None,
+                deposit_cap_per_address: None,
+                redemption_policy: RedemptionPolicy::default(),
+                reserve_ratio: Decimal256::zero(),
+                queue_redemptions: false,
+            })
+        }),
+    );
+}
+
+fn setup() -> MockDeps {
+    let mut deps = mock_dependencies(&[]);
+    for pool in ["pool-1", "pool-2", "pool-3"] {
+        mock_pool(&mut deps, pool);
+    }
+
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            pool_code_id: 100,
+            dp_code_id: 200,
+            fee_collector: "fee-collector".to_string(),
+            fee_rate: Decimal256::percent(20),
+        },
+    )
+    .expect("testing: should init contract");
+
+    deps
+}
+
+fn create_pool_msg(pool_name: &str, beneficiary: &str) -> ExecuteMsg {
+    ExecuteMsg::CreatePool {
+        pool_name: pool_name.to_string(),
+        beneficiary: beneficiary.to_string(),
+        moneymarket: "money-market".to_string(),
+        moneymarket_kind: MoneyMarketKind::Anchor,
+        fee_collector: None,
+        fee_rate: None,
+    }
+}
+
+/// the pool was instantiated at `pool`
+fn instantiate_reply(pool: &str) -> Reply {
+    let mut res = MsgInstantiateContractResponse::new();
+    res.set_contract_address(pool.to_string());
+
+    Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(Binary::from(res.write_to_bytes().unwrap())),
+        }),
+    }
+}
+
+fn create_pool(deps: &mut MockDeps, pool_name: &str, beneficiary: &str, pool: &str) {
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        create_pool_msg(pool_name, beneficiary),
+    )
+    .unwrap();
+    contract::reply(deps.as_mut(), mock_env(), instantiate_reply(pool)).unwrap();
+}
+
+fn query<T: serde::de::DeserializeOwned>(deps: &MockDeps, msg: QueryMsg) -> T {
+    from_binary(&contract::query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn instantiate() {
+    let deps = setup();
+
+    let config: ConfigResponse = query(&deps, QueryMsg::Config {});
+    assert_eq!(
+        config,
+        ConfigResponse {
+            owner: "owner".to_string(),
+            pool_code_id: 100,
+            dp_code_id: 200,
+            fee_collector: "fee-collector".to_string(),
+            fee_rate: Decimal256::percent(20),
+        }
+    );
+
+    let err = contract::instantiate(
+        mock_dependencies(&[]).as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            pool_code_id: 100,
+            dp_code_id: 200,
+            fee_collector: "fee-collector".to_string(),
+            fee_rate: Decimal256::percent(51),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFeeRate {
+            max: Decimal256::percent(50),
+            actual: Decimal256::percent(51),
+        }
+    );
+}
+
+#[test]
+fn configure() {
+    let mut deps = setup();
+
+    let msg = ExecuteMsg::Configure {
+        pool_code_id: Some(101),
+        dp_code_id: None,
+        fee_collector: Some("new-fee-collector".to_string()),
+        fee_rate: Some(Decimal256::percent(10)),
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    let config: ConfigResponse = query(&deps, QueryMsg::Config {});
+    assert_eq!(config.pool_code_id, 101);
+    assert_eq!(config.dp_code_id, 200);
+    assert_eq!(config.fee_collector, "new-fee-collector".to_string());
+    assert_eq!(config.fee_rate, Decimal256::percent(10));
+}
+
+#[test]
+fn create_pool_instantiates_with_defaults() {
+    let mut deps = setup();
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        create_pool_msg("alpha", "beneficiary"),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        create_pool_msg("", "beneficiary"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyPoolName {});
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        create_pool_msg("alpha", "beneficiary"),
+    )
+    .unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(resp.messages[0].id, 1);
+    assert_eq!(resp.messages[0].reply_on, ReplyOn::Success);
+    assert_eq!(
+        resp.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Instantiate {
+            admin: Some("owner".to_string()),
+            code_id: 100,
+            funds: vec![],
+            label: "Pylon Pool - alpha".to_string(),
+            msg: to_binary(&PoolInstantiateMsg {
+                pool_name: "alpha".to_string(),
+                beneficiary: "beneficiary".to_string(),
+                fee_collector: "fee-collector".to_string(),
+                fee_rate: Decimal256::percent(20),
+                moneymarket: "money-market".to_string(),
+                moneymarket_kind: MoneyMarketKind::Anchor,
+                dp_code_id: Some(200),
+                dp_token: None,
+                dp_token_name: None,
+                dp_token_symbol: None,
+                dp_token_marketing: None,
+                dp_token_label: None,
+                dp_token_admin: None,
+            })
+            .unwrap(),
+        })
+    );
+}
+
+#[test]
+fn reply_registers_pool() {
+    let mut deps = setup();
+
+    let err = contract::reply(deps.as_mut(), mock_env(), instantiate_reply("pool-1")).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingPool {});
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        create_pool_msg("alpha", "beneficiary"),
+    )
+    .unwrap();
+    let resp = contract::reply(deps.as_mut(), mock_env(), instantiate_reply("pool-1")).unwrap();
+    // ownership is offered to the factory owner
+    assert_eq!(
+        resp.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pool-1".to_string(),
+            msg: to_binary(&PoolExecuteMsg::TransferOwnership {
+                new_owner: "owner".to_string(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    let pool: PoolResponse = query(
+        &deps,
+        QueryMsg::PoolByName {
+            name: "alpha".to_string(),
+        },
+    );
+    assert_eq!(
+        pool,
+        PoolResponse {
+            id: 1,
+            address: "pool-1".to_string(),
+            name: "alpha".to_string(),
+            dp_token: "dp-token-1".to_string(),
+            beneficiary: "beneficiary".to_string(),
+            created_at: mock_env().block.time.seconds(),
+        }
+    );
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        create_pool_msg("alpha", "other"),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PoolAlreadyExists {
+            name: "alpha".to_string()
+        }
+    );
+
+    let err = contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 2,
+            result: ContractResult::Err("".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId { id: 2 });
+}
+
+#[test]
+fn query_pools() {
+    let mut deps = setup();
+    create_pool(&mut deps, "alpha", "alice", "pool-1");
+    create_pool(&mut deps, "beta", "bob", "pool-2");
+    create_pool(&mut deps, "gamma", "alice", "pool-3");
+
+    let names = |resp: PoolsResponse| -> Vec<String> {
+        resp.pools.into_iter().map(|pool| pool.name).collect()
+    };
+
+    let resp: PoolsResponse = query(
+        &deps,
+        QueryMsg::Pools {
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(names(resp), vec!["alpha", "beta", "gamma"]);
+
+    let resp: PoolsResponse = query(
+        &deps,
+        QueryMsg::Pools {
+            start_after: Some(1),
+            limit: Some(1),
+        },
+    );
+    assert_eq!(names(resp), vec!["beta"]);
+
+    let resp: PoolsResponse = query(
+        &deps,
+        QueryMsg::PoolsByBeneficiary {
+            beneficiary: "alice".to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(names(resp), vec!["alpha", "gamma"]);
+
+    let resp: PoolsResponse = query(
+        &deps,
+        QueryMsg::PoolsByBeneficiary {
+            beneficiary: "alice".to_string(),
+            start_after: Some(1),
+            limit: None,
+        },
+    );
+    assert_eq!(names(resp), vec!["gamma"]);
+
+    let pool: PoolResponse = query(
+        &deps,
+        QueryMsg::PoolByDpToken {
+            dp_token: "dp-token-2".to_string(),
+        },
+    );
+    assert_eq!(pool.name, "beta".to_string());
+
+    let err = contract::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::PoolByName {
+            name: "delta".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PoolNotFound {});
+}
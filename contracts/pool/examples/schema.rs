@@ -1,6 +1,6 @@
-use core_pool::config::Config;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use pylon_core::pool_msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use pylon_core_pool::config::Config;
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
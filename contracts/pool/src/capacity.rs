@@ -0,0 +1,44 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Deps, StdResult};
+use pylon_utils::token;
+use std::cmp::min;
+
+use crate::config;
+
+pub struct Capacity {
+    pub total: Option<Uint256>,
+    pub address: Option<Uint256>,
+}
+
+impl Capacity {
+    /// DP amount `owner` can still receive, `None` if neither cap is set
+    pub fn remaining(&self) -> Option<Uint256> {
+        match (self.total, self.address) {
+            (Some(total), Some(address)) => Some(min(total, address)),
+            (total, address) => total.or(address),
+        }
+    }
+}
+
+fn remaining(cap: Uint256, used: Uint256) -> Uint256 {
+    if cap > used {
+        cap - used
+    } else {
+        Uint256::zero()
+    }
+}
+
+pub fn of(deps: Deps, config: &config::Config, owner: String) -> StdResult<Capacity> {
+    let dp_token = deps.api.addr_humanize(&config.dp_token)?.to_string();
+
+    let total = match config.deposit_cap {
+        Some(cap) => Some(remaining(cap, token::total_supply(deps, dp_token.clone())?)),
+        None => None,
+    };
+    let address = match config.deposit_cap_per_address {
+        Some(cap) => Some(remaining(cap, token::balance_of(deps, dp_token, owner)?)),
+        None => None,
+    };
+
+    Ok(Capacity { total, address })
+}
@@ -1,21 +1,137 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{CanonicalAddr, StdResult, Storage};
-use cosmwasm_storage::{singleton, singleton_read};
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{Addr, Api, CanonicalAddr, StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read, ReadonlySingleton, Singleton};
+use pylon_core::address;
+use pylon_core::pool_msg::{MoneyMarketKind, RedemptionPolicy};
+
+use crate::error::ContractError;
 
 pub static CONFIG_KEY: &[u8] = b"config";
+pub static PENDING_OWNER_KEY: &[u8] = b"pending_owner";
+pub static PENDING_DP_TOKEN_KEY: &[u8] = b"pending_dp_token";
+
+/// Hard upper bound of the protocol fee rate (50%)
+pub fn max_fee_rate() -> Decimal256 {
+    Decimal256::percent(50)
+}
+
+pub fn validate_fee_rate(fee_rate: Decimal256) -> Result<(), ContractError> {
+    if fee_rate > max_fee_rate() {
+        return Err(ContractError::InvalidFeeRate {
+            max: max_fee_rate(),
+            actual: fee_rate,
+        });
+    }
+
+    Ok(())
+}
+
+/// Hard upper bound of the keeper bounty rate (10%)
+pub fn max_bounty_rate() -> Decimal256 {
+    Decimal256::percent(10)
+}
+
+pub fn validate_bounty_rate(bounty_rate: Decimal256) -> Result<(), ContractError> {
+    if bounty_rate > max_bounty_rate() {
+        return Err(ContractError::InvalidBountyRate {
+            max: max_bounty_rate(),
+            actual: bounty_rate,
+        });
+    }
+
+    Ok(())
+}
+
+/// Hard upper bound of the reserve ratio (50%)
+pub fn max_reserve_ratio() -> Decimal256 {
+    Decimal256::percent(50)
+}
+
+pub fn validate_reserve_ratio(reserve_ratio: Decimal256) -> Result<(), ContractError> {
+    if reserve_ratio > max_reserve_ratio() {
+        return Err(ContractError::InvalidReserveRatio {
+            max: max_reserve_ratio(),
+            actual: reserve_ratio,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn validate_beneficiaries(beneficiaries: &[Beneficiary]) -> Result<(), ContractError> {
+    let mut total_weight: u64 = 0;
+    for (i, beneficiary) in beneficiaries.iter().enumerate() {
+        if beneficiary.weight == 0 {
+            return Err(ContractError::InvalidBeneficiaries {
+                reason: "zero weight".to_string(),
+            });
+        }
+        if beneficiaries[..i]
+            .iter()
+            .any(|other| other.address == beneficiary.address)
+        {
+            return Err(ContractError::InvalidBeneficiaries {
+                reason: "duplicated address".to_string(),
+            });
+        }
+        total_weight = total_weight
+            .checked_add(beneficiary.weight)
+            .ok_or_else(|| ContractError::InvalidBeneficiaries {
+                reason: "total weight overflow".to_string(),
+            })?;
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Beneficiary {
+    pub address: CanonicalAddr,
+    pub weight: u64,
+}
+
+/// Lets anyone call earn once `min_interval` seconds passed since the last earn
+/// or the earnable amount reached `min_earnable`, paying `bounty_rate` of it to the caller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Keeper {
+    pub min_interval: u64,
+    pub min_earnable: Uint256,
+    pub bounty_rate: Decimal256,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
     pub this: CanonicalAddr,
     pub owner: CanonicalAddr,
+    pub pauser: Option<CanonicalAddr>,
     pub beneficiary: CanonicalAddr,
+    pub beneficiaries: Vec<Beneficiary>,
     pub fee_collector: CanonicalAddr,
+    pub fee_rate: Decimal256,
+    pub keeper: Option<Keeper>,
     pub moneymarket: CanonicalAddr,
+    pub moneymarket_kind: MoneyMarketKind,
     pub atoken: CanonicalAddr,
     pub stable_denom: String,
     pub dp_token: CanonicalAddr,
+    pub deposit_cap: Option<Uint256>,
+    pub deposit_cap_per_address: Option<Uint256>,
+    pub redemption_policy: RedemptionPolicy,
+    pub reserve_ratio: Decimal256,
+    pub queue_redemptions: bool,
+}
+
+impl Config {
+    /// DP token is set by the instantiate reply or `adopt_dp_token`, it stays empty until then
+    pub fn dp_token_addr(&self, api: &dyn Api) -> Result<Addr, ContractError> {
+        if self.dp_token.as_slice().is_empty() {
+            return Err(ContractError::DpTokenNotRegistered {});
+        }
+        Ok(address::humanize(api, &self.dp_token)?)
+    }
 }
 
 pub fn store(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
@@ -25,3 +141,41 @@ pub fn store(storage: &mut dyn Storage, data: &Config) -> StdResult<()> {
 pub fn read(storage: &dyn Storage) -> StdResult<Config> {
     singleton_read(storage, CONFIG_KEY).load()
 }
+
+/// same as [read], but tells a pool that was never instantiated apart from other storage errors
+pub fn load(storage: &dyn Storage) -> Result<Config, ContractError> {
+    singleton_read(storage, CONFIG_KEY)
+        .may_load()?
+        .ok_or(ContractError::ConfigNotFound {})
+}
+
+pub fn store_pending_owner(storage: &mut dyn Storage, owner: &CanonicalAddr) -> StdResult<()> {
+    singleton(storage, PENDING_OWNER_KEY).save(owner)
+}
+
+pub fn read_pending_owner(storage: &dyn Storage) -> StdResult<Option<CanonicalAddr>> {
+    let pending_owner: ReadonlySingleton<CanonicalAddr> =
+        singleton_read(storage, PENDING_OWNER_KEY);
+    pending_owner.may_load()
+}
+
+pub fn remove_pending_owner(storage: &mut dyn Storage) {
+    let mut pending_owner: Singleton<CanonicalAddr> = singleton(storage, PENDING_OWNER_KEY);
+    pending_owner.remove()
+}
+
+/// existing DP token given at instantiation, waiting for its minter to be handed to the pool
+pub fn store_pending_dp_token(storage: &mut dyn Storage, token: &CanonicalAddr) -> StdResult<()> {
+    singleton(storage, PENDING_DP_TOKEN_KEY).save(token)
+}
+
+pub fn read_pending_dp_token(storage: &dyn Storage) -> StdResult<Option<CanonicalAddr>> {
+    let pending_dp_token: ReadonlySingleton<CanonicalAddr> =
+        singleton_read(storage, PENDING_DP_TOKEN_KEY);
+    pending_dp_token.may_load()
+}
+
+pub fn remove_pending_dp_token(storage: &mut dyn Storage) {
+    let mut pending_dp_token: Singleton<CanonicalAddr> = singleton(storage, PENDING_DP_TOKEN_KEY);
+    pending_dp_token.remove()
+}
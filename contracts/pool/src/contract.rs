@@ -1,22 +1,30 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
+use cosmwasm_bignumber::Decimal256;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn,
-    Response, StdError, StdResult, SubMsg, WasmMsg,
+    to_binary, Binary, CanonicalAddr, Deps, DepsMut, Env, MessageInfo, Reply, ReplyOn, Response,
+    SubMsg, WasmMsg,
 };
 use cw20::MinterResponse;
 use protobuf::Message;
-use pylon_core::pool_msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use terraswap::token::InstantiateMsg as Cw20InstantiateMsg;
+use pylon_core::address;
+use pylon_core::pool_msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RedemptionPolicy};
+use semver::Version;
 
 use crate::error::ContractError;
 use crate::handler::core as CoreHandler;
 use crate::handler::query as QueryHandler;
-use crate::response::MsgInstantiateContractResponse;
-use crate::{config, querier};
+use crate::{config, dp_token, migration, querier};
+use pylon_core::response::MsgInstantiateContractResponse;
+
+const CONTRACT_NAME: &str = "crates.io:pylon-core-pool";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const INSTANTIATE_REPLY_ID: u64 = 1;
+pub(crate) const REDEEM_REPLY_ID: u64 = 2;
+pub(crate) const REFILL_REPLY_ID: u64 = 3;
+pub(crate) const EARN_REPLY_ID: u64 = 4;
 
 #[allow(dead_code)]
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -26,42 +34,75 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    config::validate_fee_rate(msg.fee_rate)?;
+
     let mut config = config::Config {
         this: deps.api.addr_canonicalize(env.contract.address.as_str())?,
         owner: deps.api.addr_canonicalize(info.sender.as_str())?,
+        pauser: None,
         beneficiary: deps.api.addr_canonicalize(msg.beneficiary.as_str())?,
+        beneficiaries: vec![],
         fee_collector: deps.api.addr_canonicalize(msg.fee_collector.as_str())?,
+        fee_rate: msg.fee_rate,
+        keeper: None,
         moneymarket: deps.api.addr_canonicalize(msg.moneymarket.as_str())?,
+        moneymarket_kind: msg.moneymarket_kind.clone(),
         stable_denom: String::default(),
         atoken: CanonicalAddr::from(vec![]),
         dp_token: CanonicalAddr::from(vec![]),
+        deposit_cap: None,
+        deposit_cap_per_address: None,
+        redemption_policy: RedemptionPolicy::default(),
+        reserve_ratio: Decimal256::zero(),
+        queue_redemptions: false,
     };
 
-    let market_config = querier::anchor::config(deps.as_ref(), &config.moneymarket)?;
+    let market_info = querier::moneymarket::describe(
+        deps.as_ref(),
+        &config.moneymarket_kind,
+        &config.moneymarket,
+    )?;
 
-    config.stable_denom = market_config.stable_denom.clone();
-    config.atoken = deps
-        .api
-        .addr_canonicalize(market_config.aterra_contract.as_str())?;
+    config.stable_denom = market_info.stable_denom;
+    config.atoken = market_info.share_token;
 
     config::store(deps.storage, &config)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if let Some(dp_token) = msg.dp_token {
+        let dp_token = address::validate(deps.api, dp_token.as_str())?;
+        return CoreHandler::propose_dp_token(deps, env, info, dp_token);
+    }
+    let dp_code_id = msg.dp_code_id.ok_or(ContractError::DpCodeIdMissing {})?;
+
+    let dp_name = match msg.dp_token_name {
+        Some(name) => name,
+        None => dp_token::default_name(&msg.pool_name),
+    };
+    let dp_admin = match msg.dp_token_admin {
+        Some(admin) => address::validate(deps.api, admin.as_str())?,
+        None => env.contract.address.clone(),
+    };
 
     Ok(Response::new().add_submessage(SubMsg {
         // Create DP token
         msg: WasmMsg::Instantiate {
-            admin: None,
-            code_id: msg.dp_code_id,
+            admin: Some(dp_admin.to_string()),
+            code_id: dp_code_id,
             funds: vec![],
-            label: "".to_string(),
-            msg: to_binary(&Cw20InstantiateMsg {
-                name: format!("Deposit Token - {}", msg.pool_name),
-                symbol: "PylonDP".to_string(),
-                decimals: 6,
+            label: msg.dp_token_label.unwrap_or_else(|| dp_name.clone()),
+            msg: to_binary(&dp_token::InstantiateMsg {
+                name: dp_name,
+                symbol: msg
+                    .dp_token_symbol
+                    .unwrap_or_else(|| dp_token::DEFAULT_SYMBOL.to_string()),
+                decimals: dp_token::DECIMALS,
                 initial_balances: vec![],
                 mint: Some(MinterResponse {
                     minter: env.contract.address.to_string(),
                     cap: None,
                 }),
+                marketing: msg.dp_token_marketing,
             })?,
         }
         .into(),
@@ -81,12 +122,60 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => CoreHandler::receive(deps, env, info, msg),
-        ExecuteMsg::Deposit {} => CoreHandler::deposit(deps, env, info),
+        ExecuteMsg::Deposit {
+            recipient,
+            min_mint,
+        } => CoreHandler::deposit(deps, env, info, recipient, min_mint),
         ExecuteMsg::Earn {} => CoreHandler::earn(deps, env, info),
         ExecuteMsg::Configure {
             beneficiary,
             fee_collector,
-        } => CoreHandler::configure(deps, env, info, beneficiary, fee_collector),
+            fee_rate,
+            pauser,
+            deposit_cap,
+            deposit_cap_per_address,
+            beneficiaries,
+            redemption_policy,
+            reserve_ratio,
+            queue_redemptions,
+        } => CoreHandler::configure(
+            deps,
+            env,
+            info,
+            beneficiary,
+            fee_collector,
+            fee_rate,
+            pauser,
+            deposit_cap,
+            deposit_cap_per_address,
+            beneficiaries,
+            redemption_policy,
+            reserve_ratio,
+            queue_redemptions,
+        ),
+        ExecuteMsg::ConfigureKeeper { keeper } => {
+            CoreHandler::configure_keeper(deps, env, info, keeper)
+        }
+        ExecuteMsg::SetPause {
+            deposit,
+            redeem,
+            earn,
+        } => CoreHandler::set_pause(deps, env, info, deposit, redeem, earn),
+        ExecuteMsg::ClearPauser {} => CoreHandler::clear_pauser(deps, env, info),
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            CoreHandler::transfer_ownership(deps, env, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => CoreHandler::accept_ownership(deps, env, info),
+        ExecuteMsg::CancelOwnershipTransfer {} => {
+            CoreHandler::cancel_ownership_transfer(deps, env, info)
+        }
+        ExecuteMsg::Rebalance {} => CoreHandler::rebalance(deps, env, info),
+        ExecuteMsg::ProcessRedemptions {} => CoreHandler::process_redemptions(deps, env, info),
+        ExecuteMsg::SyncBalances { owners } => CoreHandler::sync_balances(deps, env, info, owners),
+        ExecuteMsg::AdoptDpToken {} => CoreHandler::adopt_dp_token(deps, env, info),
+        ExecuteMsg::MigrateDpToken { code_id, msg } => {
+            CoreHandler::migrate_dp_token(deps, env, info, code_id, msg)
+        }
     }
 }
 
@@ -96,36 +185,101 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractEr
     match msg.id {
         INSTANTIATE_REPLY_ID => {
             // get new token's contract address
-            let res: MsgInstantiateContractResponse = Message::parse_from_bytes(
-                msg.result.unwrap().data.unwrap().as_slice(),
-            )
-            .map_err(|_| {
-                ContractError::Std(StdError::parse_err(
-                    "MsgInstantiateContractResponse",
-                    "failed to parse data",
-                ))
-            })?;
-            let token_addr = Addr::unchecked(res.get_contract_address());
+            let id = msg.id;
+            let malformed = |reason: &str| ContractError::MalformedReply {
+                id,
+                reason: reason.to_string(),
+            };
+            let data = msg
+                .result
+                .into_result()
+                .map_err(|err| malformed(err.as_str()))?
+                .data
+                .ok_or_else(|| malformed("missing instantiate response data"))?;
+            let res: MsgInstantiateContractResponse = Message::parse_from_bytes(data.as_slice())
+                .map_err(|_| malformed("failed to parse MsgInstantiateContractResponse"))?;
+            let token_addr = address::validate(deps.api, res.get_contract_address())?;
 
             CoreHandler::register_dp_token(deps, env, token_addr)
         }
+        REDEEM_REPLY_ID => CoreHandler::settle_redemptions(deps, env),
+        REFILL_REPLY_ID => CoreHandler::refill_reserve(deps, env),
+        EARN_REPLY_ID => CoreHandler::distribute_reward(deps, env),
         _ => Err(ContractError::InvalidReplyId { id: msg.id }),
     }
 }
 
 #[allow(dead_code)]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::DepositAmountOf { owner } => QueryHandler::deposit_amount(deps, env, owner), // dp_token.balanceOf(msg.sender)
         QueryMsg::TotalDepositAmount {} => QueryHandler::total_deposit_amount(deps, env), // dp_token.totalSupply()
         QueryMsg::Config {} => QueryHandler::config(deps, env),                           // config
         QueryMsg::ClaimableReward {} => QueryHandler::claimable_reward(deps, env), // config.strategy.reward()
+        QueryMsg::PauseInfo {} => QueryHandler::pause_info(deps, env),
+        QueryMsg::DepositCapacityOf { owner } => QueryHandler::deposit_capacity(deps, env, owner),
+        QueryMsg::DepositBalanceSecondsOf { owner, at } => {
+            QueryHandler::deposit_balance_seconds(deps, env, owner, at)
+        }
+        QueryMsg::DepositShareOf { owner, start, end } => {
+            QueryHandler::deposit_share(deps, env, owner, start, end)
+        }
+        QueryMsg::DepositAmountAt { owner, height } => {
+            QueryHandler::deposit_amount_at(deps, env, owner, height)
+        }
+        QueryMsg::TotalDepositAmountAt { height } => {
+            QueryHandler::total_deposit_amount_at(deps, env, height)
+        }
+        QueryMsg::PoolInfo {} => QueryHandler::pool_info(deps, env),
+        QueryMsg::SimulateDeposit { amount } => QueryHandler::simulate_deposit(deps, env, amount),
+        QueryMsg::SimulateRedeem { amount } => QueryHandler::simulate_redeem(deps, env, amount),
+        QueryMsg::EarnHistory { start_after, limit } => {
+            QueryHandler::earn_history(deps, env, start_after, limit)
+        }
+        QueryMsg::Solvency {} => QueryHandler::solvency(deps, env),
+        QueryMsg::RedemptionTicketsOf {
+            owner,
+            start_after,
+            limit,
+        } => QueryHandler::redemption_tickets(deps, env, owner, start_after, limit),
+        QueryMsg::RedemptionQueue {} => QueryHandler::redemption_queue(deps, env),
     }
 }
 
 #[allow(dead_code)]
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let current = parse_version(CONTRACT_VERSION)?;
+
+    match cw2::CONTRACT.may_load(deps.storage)? {
+        // pools deployed before versioning, the only layout to upgrade
+        None => migration::from_legacy(deps.branch(), &env)?,
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::MigrationContractMismatch {
+                    expected: CONTRACT_NAME.to_string(),
+                    actual: stored.contract,
+                });
+            }
+            if parse_version(&stored.version)? > current {
+                return Err(ContractError::MigrationDowngrade {
+                    stored: stored.version,
+                    current: CONTRACT_VERSION.to_string(),
+                });
+            }
+        }
+    }
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("version", CONTRACT_VERSION))
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidVersion {
+        version: version.to_string(),
+    })
 }
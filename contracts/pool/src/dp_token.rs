@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Deps, StdResult};
+use cw20::{Cw20Coin, Cw20QueryMsg, MinterResponse};
+use pylon_core::pool_msg::DpTokenMarketing;
+
+pub const DEFAULT_SYMBOL: &str = "PylonDP";
+pub const DECIMALS: u8 = 6;
+
+/// Instantiate message of the DP token contract. marketing info is left out unless given,
+/// so token contracts without marketing support keep accepting it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<Cw20Coin>,
+    pub mint: Option<MinterResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marketing: Option<DpTokenMarketing>,
+}
+
+pub fn default_name(pool_name: &str) -> String {
+    format!("Deposit Token - {}", pool_name)
+}
+
+/// address allowed to mint `token`, if any
+pub fn minter(deps: Deps, token: &Addr) -> StdResult<Option<String>> {
+    let minter: Option<MinterResponse> = deps
+        .querier
+        .query_wasm_smart(token.to_string(), &Cw20QueryMsg::Minter {})?;
+
+    Ok(minter.map(|minter| minter.minter))
+}
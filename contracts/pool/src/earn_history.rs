@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_storage::{bucket, bucket_read};
+use pylon_utils::range::calc_range_start;
+
+pub static PREFIX_EARN_HISTORY: &[u8] = b"earn_history";
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Harvest {
+    pub id: u64,
+    pub height: u64,
+    pub time: u64,
+    pub exchange_rate: Decimal256,
+    pub earnable: Uint256,
+    pub amount: Uint256,
+    pub fee: Uint256,
+    pub bounty: Uint256,
+    pub caller: CanonicalAddr,
+}
+
+/// id the next recorded harvest will get, starting from 1
+pub fn next_id(storage: &dyn Storage) -> StdResult<u64> {
+    let last = bucket_read::<Harvest>(storage, PREFIX_EARN_HISTORY)
+        .range(None, None, Order::Descending)
+        .next()
+        .transpose()?;
+
+    Ok(last.map(|(_, harvest)| harvest.id + 1).unwrap_or(1))
+}
+
+pub fn store(storage: &mut dyn Storage, harvest: &Harvest) -> StdResult<()> {
+    bucket(storage, PREFIX_EARN_HISTORY).save(&harvest.id.to_be_bytes(), harvest)
+}
+
+/// harvests in ascending order of id
+pub fn read(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Harvest>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    bucket_read(storage, PREFIX_EARN_HISTORY)
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, harvest)| harvest))
+        .collect()
+}
@@ -1,4 +1,6 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::{OverflowError, StdError};
+use pylon_core::address::InvalidAddress;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -18,9 +20,62 @@ pub enum ContractError {
         actual: String,
     },
 
+    #[error("Core/Pool: Cannot migrate from contract {actual:?} (expected: {expected:?})")]
+    MigrationContractMismatch { expected: String, actual: String },
+
+    #[error("Core/Pool: Cannot downgrade contract (stored: {stored:?}, current: {current:?})")]
+    MigrationDowngrade { stored: String, current: String },
+
+    #[error("Core/Pool: Invalid contract version {version:?}")]
+    InvalidVersion { version: String },
+
     #[error("Core/Pool: Invalid reply ID (ID: {id:?}")]
     InvalidReplyId { id: u64 },
 
+    #[error("Core/Pool: Malformed reply (ID: {id}, reason: {reason})")]
+    MalformedReply { id: u64, reason: String },
+
+    #[error("Core/Pool: Money market returned no redeem messages")]
+    NoRedeemMessages {},
+
+    #[error("Core/Pool: No redemption is waiting for settlement")]
+    NoPendingSettlement {},
+
+    #[error("Core/Pool: No reserve refill is waiting for the money market")]
+    NoPendingRefill {},
+
+    #[error("Core/Pool: No earned yield is waiting for the money market")]
+    NoPendingClaim {},
+
+    #[error(
+        "Core/Pool: DP token minter must be the pool (expected: {expected:?}, actual: {actual:?})"
+    )]
+    DpTokenMinterMismatch { expected: String, actual: String },
+
+    #[error("Core/Pool: Migrated assets do not cover the DP supply (dp_total_supply: {dp_total_supply}, pool_value_locked: {pool_value_locked})")]
+    MigratedAssetsShort {
+        dp_total_supply: Uint256,
+        pool_value_locked: Uint256,
+    },
+
+    #[error("Core/Pool: No DP token is waiting for adoption")]
+    NoPendingDpToken {},
+
+    #[error("Core/Pool: UST backing an adopted DP token is sent with adopt_dp_token")]
+    AdoptionFundsNotExpected {},
+
+    #[error("Core/Pool: dp_code_id is required unless dp_token is given")]
+    DpCodeIdMissing {},
+
+    #[error("Core/Pool: Config not found")]
+    ConfigNotFound {},
+
+    #[error("Core/Pool: Invalid address {address:?}")]
+    InvalidAddress { address: String },
+
+    #[error("Core/Pool: DP token is not registered yet")]
+    DpTokenNotRegistered {},
+
     #[error("Core/Pool: Zero amount not allowed")]
     NotAllowZeroAmount {},
 
@@ -29,4 +84,62 @@ pub enum ContractError {
 
     #[error("Core/Pool: other action except {action:?} is not allowed")]
     NotAllowOtherCw20ReceiveAction { action: String },
+
+    #[error("Core/Pool: Deposit is paused")]
+    DepositPaused {},
+
+    #[error("Core/Pool: Redeem is paused")]
+    RedeemPaused {},
+
+    #[error("Core/Pool: Earn is paused")]
+    EarnPaused {},
+
+    #[error("Core/Pool: No pauser is set")]
+    NoPauser {},
+
+    #[error("Core/Pool: No pending ownership transfer")]
+    NoPendingOwner {},
+
+    #[error("Core/Pool: Deposit cap exceeded (available: {available}, requested: {requested})")]
+    DepositCapExceeded {
+        available: Uint256,
+        requested: Uint256,
+    },
+
+    #[error("Core/Pool: Slippage exceeded (minimum: {minimum}, actual: {actual})")]
+    SlippageExceeded { minimum: Uint256, actual: Uint256 },
+
+    #[error("Core/Pool: Invalid bounty rate (max: {max}, actual: {actual})")]
+    InvalidBountyRate { max: Decimal256, actual: Decimal256 },
+
+    #[error("Core/Pool: Earn is not open to keepers yet (next: {next_earn_at}, min earnable: {min_earnable})")]
+    EarnNotReady {
+        next_earn_at: u64,
+        min_earnable: Uint256,
+    },
+
+    #[error("Core/Pool: Pool is in deficit (deficit: {deficit})")]
+    PoolInDeficit { deficit: Uint256 },
+
+    #[error("Core/Pool: Invalid beneficiaries ({reason})")]
+    InvalidBeneficiaries { reason: String },
+
+    #[error("Core/Pool: Invalid fee rate (max: {max}, actual: {actual})")]
+    InvalidFeeRate { max: Decimal256, actual: Decimal256 },
+
+    #[error("Core/Pool: Invalid reserve ratio (max: {max}, actual: {actual})")]
+    InvalidReserveRatio { max: Decimal256, actual: Decimal256 },
+
+    #[error(
+        "Core/Pool: Share period start must not be after its end (start: {start}, end: {end})"
+    )]
+    InvalidSharePeriod { start: u64, end: u64 },
+}
+
+impl From<InvalidAddress> for ContractError {
+    fn from(err: InvalidAddress) -> Self {
+        ContractError::InvalidAddress {
+            address: err.address,
+        }
+    }
 }
@@ -1,15 +1,29 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::*;
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use pylon_core::pool_msg::Cw20HookMsg;
+use pylon_core::address;
+use pylon_core::pool_msg::{
+    Beneficiary as BeneficiaryMsg, Cw20HookMsg, Keeper as KeeperMsg, RedemptionPolicy,
+};
 use pylon_utils::tax::deduct_tax;
 use pylon_utils::token;
-use std::ops::{Div, Mul, Sub};
-use std::str::FromStr;
+use std::ops::Div;
 
+use crate::capacity;
 use crate::config;
+use crate::contract;
+use crate::dp_token;
+use crate::earn_history;
 use crate::error::ContractError;
-use crate::querier::anchor;
+use crate::pause;
+use crate::querier::moneymarket;
+use crate::redemption_queue;
+use crate::reward;
+use crate::settlement;
+use crate::simulation;
+use crate::snapshot;
+use crate::state;
+use crate::time_weight;
 
 pub fn receive(
     deps: DepsMut,
@@ -17,32 +31,59 @@ pub fn receive(
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
-    match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Redeem {}) => {
-            // only asset contract can execute this message
-            let config: config::Config = config::read(deps.storage).unwrap();
-            if deps.api.addr_canonicalize(info.sender.as_str()).unwrap() != config.dp_token {
-                return Err(ContractError::Unauthorized {
-                    action: "receive".to_string(),
-                    expected: deps
-                        .api
-                        .addr_humanize(&config.dp_token)
-                        .unwrap()
-                        .to_string(),
-                    actual: info.sender.to_string(),
-                });
-            }
-
-            redeem(deps, env, info, cw20_msg.sender, cw20_msg.amount)
+    let hook_msg = match from_binary(&cw20_msg.msg) {
+        Ok(hook_msg) => hook_msg,
+        Err(_) => {
+            return Err(ContractError::NotAllowOtherCw20ReceiveAction {
+                action: "redeem".to_string(),
+            })
+        }
+    };
+
+    // only asset contract can execute this message
+    let config = config::load(deps.storage)?;
+    let dp_token = config.dp_token_addr(deps.api)?;
+    if info.sender != dp_token {
+        return Err(ContractError::Unauthorized {
+            action: "receive".to_string(),
+            expected: dp_token.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    match hook_msg {
+        Cw20HookMsg::Redeem {
+            recipient,
+            min_receive,
+        } => redeem(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            recipient,
+            cw20_msg.amount,
+            min_receive,
+        ),
+        Cw20HookMsg::Transfer { recipient } => {
+            transfer(deps, env, info, cw20_msg.sender, recipient, cw20_msg.amount)
         }
-        _ => Err(ContractError::NotAllowOtherCw20ReceiveAction {
-            action: "redeem".to_string(),
-        }),
     }
 }
 
-pub fn deposit(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    let config = config::read(deps.storage).unwrap();
+pub fn deposit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+    min_mint: Option<Uint256>,
+) -> Result<Response, ContractError> {
+    pause::assert_deposit_allowed(deps.storage)?;
+    let config = config::load(deps.storage)?;
+    let dp_token = config.dp_token_addr(deps.api)?.to_string();
+    let recipient = match recipient {
+        Some(recipient) => address::validate(deps.api, recipient.as_str())?,
+        None => info.sender.clone(),
+    };
 
     // check deposit
     let received: Uint256 = info
@@ -61,225 +102,1096 @@ pub fn deposit(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response,
         });
     }
 
-    let dp_mint_amount = deduct_tax(
-        deps.as_ref(),
-        Coin {
-            denom: config.stable_denom.clone(),
-            amount: received.into(),
-        },
-    )?
-    .amount;
+    let simulation = simulation::deposit(deps.as_ref(), &config, received)?;
+    assert_minimum(min_mint, simulation.dp_mint_amount)?;
+    let dp_mint_amount: Uint128 = simulation.dp_mint_amount.into();
+
+    let capacity = capacity::of(deps.as_ref(), &config, recipient.to_string())?;
+    if let Some(available) = capacity.remaining() {
+        if Uint256::from(dp_mint_amount) > available {
+            return Err(ContractError::DepositCapExceeded {
+                available,
+                requested: dp_mint_amount.into(),
+            });
+        }
+    }
+
+    // record balances as they will be once DP is minted
+    sync_holder(
+        deps.branch(),
+        &env,
+        &dp_token,
+        &recipient,
+        dp_mint_amount.into(),
+    )?;
+    time_weight::increase_total(
+        deps.storage,
+        env.block.time.seconds(),
+        dp_mint_amount.into(),
+    )?;
+
+    let mut state = state::read(deps.storage)?;
+    state.total_deposited += dp_mint_amount.into();
+    state.reserve += simulation.reserve_amount;
+    state::store(deps.storage, &state)?;
+
+    let dp_total_supply = token::total_supply(deps.as_ref(), dp_token.clone())?;
+    snapshot::store_total_deposit_amount(
+        deps.storage,
+        env.block.height,
+        dp_total_supply + dp_mint_amount.into(),
+    )?;
+
+    // whatever the reserve does not keep goes to the money market
+    let market_amount = received - simulation.reserve_amount;
+    let market_msgs = if market_amount.is_zero() {
+        vec![]
+    } else {
+        moneymarket::load(&config).deposit_msgs(deps.as_ref(), market_amount.into())?
+    };
 
     Ok(Response::new()
-        .add_messages(anchor::deposit_stable_msg(
-            deps.as_ref(),
-            &config.moneymarket,
-            &config.stable_denom,
-            received.into(),
-        )?)
+        .add_messages(market_msgs)
         .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps
-                .api
-                .addr_humanize(&config.dp_token)
-                .unwrap()
-                .to_string(),
+            contract_addr: dp_token,
             msg: to_binary(&Cw20ExecuteMsg::Mint {
-                recipient: info.sender.to_string(),
+                recipient: recipient.to_string(),
                 amount: dp_mint_amount,
             })?,
             funds: vec![],
         }))
         .add_attribute("action", "deposit")
         .add_attribute("sender", info.sender.to_string())
-        .add_attribute("amount", dp_mint_amount.to_string()))
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", dp_mint_amount.to_string())
+        .add_attribute("reserve_amount", simulation.reserve_amount.to_string()))
 }
 
 pub fn redeem(
-    deps: DepsMut,
-    _env: Env,
+    mut deps: DepsMut,
+    env: Env,
     _info: MessageInfo,
     sender: String,
+    recipient: Option<String>,
     amount: Uint128,
+    min_receive: Option<Uint256>,
 ) -> Result<Response, ContractError> {
-    let config = config::read(deps.storage).unwrap();
+    pause::assert_redeem_allowed(deps.storage)?;
+    let config = config::load(deps.storage)?;
+    let dp_token = config.dp_token_addr(deps.api)?.to_string();
+    let recipient = match recipient {
+        Some(recipient) => address::validate(deps.api, recipient.as_str())?.to_string(),
+        None => sender.clone(),
+    };
 
-    let epoch_state = anchor::epoch_state(deps.as_ref(), &config.moneymarket)?;
-    let market_redeem_amount = Uint256::from(amount).div(epoch_state.exchange_rate);
-    let user_redeem_amount = deduct_tax(
-        deps.as_ref(),
-        Coin {
-            denom: config.stable_denom.clone(),
-            amount: deduct_tax(
-                deps.as_ref(),
-                Coin {
-                    denom: config.stable_denom.clone(),
-                    amount,
-                },
-            )
-            .unwrap()
-            .amount,
-        },
-    )
-    .unwrap();
+    let simulation = simulation::redeem(deps.as_ref(), &env, &config, amount.into())?;
+    assert_minimum(min_receive, simulation.stable_amount)?;
+
+    let owner = address::canonicalize(deps.api, sender.as_str())?;
+    // redeemed DP was already sent to the pool, only the burn is pending
+    sync_holder(
+        deps.branch(),
+        &env,
+        &dp_token,
+        &Addr::unchecked(sender.as_str()),
+        Uint256::zero(),
+    )?;
+    time_weight::decrease_total(deps.storage, env.block.time.seconds(), amount.into())?;
+
+    // later redemptions wait behind the queue, so tickets are filled in order
+    if config.queue_redemptions
+        && (redemption_queue::read(deps.storage)?.depth > 0
+            || simulation.market_amount > moneymarket::load(&config).liquidity(deps.as_ref())?)
+    {
+        let ticket = redemption_queue::push(
+            deps.storage,
+            owner,
+            address::canonicalize(deps.api, recipient.as_str())?,
+            amount.into(),
+            min_receive.unwrap_or_default(),
+            env.block.time.seconds(),
+        )?;
+
+        return Ok(Response::new()
+            .add_attribute("action", "queue_redemption")
+            .add_attribute("sender", sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("ticket", ticket.id.to_string())
+            .add_attribute("amount", amount.to_string()));
+    }
+
+    let dp_total_supply = token::total_supply(deps.as_ref(), dp_token.clone())?;
+    snapshot::store_total_deposit_amount(
+        deps.storage,
+        env.block.height,
+        dp_total_supply - amount.into(),
+    )?;
+
+    let user_redeem_amount = coin(
+        u128::from(simulation.stable_amount),
+        config.stable_denom.clone(),
+    );
+    let haircut = simulation.haircut;
+    let reserve_amount = simulation.reserve_amount;
+    let recipient_raw = address::canonicalize(deps.api, &recipient)?;
+
+    Ok(Response::new()
+        .add_submessages(redemption_msgs(
+            deps.branch(),
+            &env,
+            &config,
+            dp_token,
+            vec![Redemption {
+                recipient: recipient_raw,
+                amount: amount.into(),
+                min_receive: min_receive.unwrap_or_default(),
+                simulation,
+            }],
+        )?)
+        .add_attribute("action", "redeem")
+        .add_attribute("sender", sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", user_redeem_amount.to_string())
+        .add_attribute("haircut", haircut.to_string())
+        .add_attribute("reserve_amount", reserve_amount.to_string()))
+}
+
+pub fn transfer(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    sender: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    let dp_token = config.dp_token_addr(deps.api)?.to_string();
+    let recipient = address::validate(deps.api, recipient.as_str())?;
+
+    // transferred DP sits in the pool until it is forwarded below
+    sync_holder(
+        deps.branch(),
+        &env,
+        &dp_token,
+        &Addr::unchecked(sender.as_str()),
+        Uint256::zero(),
+    )?;
+    sync_holder(deps.branch(), &env, &dp_token, &recipient, amount.into())?;
 
     Ok(Response::new()
         .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: deps
-                .api
-                .addr_humanize(&config.dp_token)
-                .unwrap()
-                .to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            contract_addr: dp_token,
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
             funds: vec![],
         }))
-        .add_messages(anchor::redeem_stable_msg(
-            deps.as_ref(),
-            &config.moneymarket,
-            &config.atoken,
-            market_redeem_amount.into(),
-        )?)
-        .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: sender.clone(),
-            amount: vec![coin(
-                u128::from(user_redeem_amount.amount),
-                user_redeem_amount.denom.clone(),
-            )],
-        }))
-        .add_attribute("action", "redeem")
+        .add_attribute("action", "transfer")
         .add_attribute("sender", sender)
-        .add_attribute("amount", user_redeem_amount.to_string()))
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn sync_balances(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owners: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    // a DP token handed over ahead of `adopt_dp_token` already reports its transfers
+    if config.dp_token.as_slice().is_empty() {
+        return Ok(Response::new()
+            .add_attribute("action", "sync_balances")
+            .add_attribute("sender", info.sender.to_string())
+            .add_attribute("owners", "0"));
+    }
+    let dp_token = config.dp_token_addr(deps.api)?.to_string();
+
+    for owner in owners.iter() {
+        let owner = address::validate(deps.api, owner.as_str())?;
+        sync_holder(deps.branch(), &env, &dp_token, &owner, Uint256::zero())?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "sync_balances")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("owners", owners.len().to_string()))
+}
+
+/// records the DP balance of `holder` for time weights and snapshots, counting `incoming` DP
+/// which is minted or sent to it later in this transaction
+fn sync_holder(
+    deps: DepsMut,
+    env: &Env,
+    dp_token: &str,
+    holder: &Addr,
+    incoming: Uint256,
+) -> Result<Uint256, ContractError> {
+    let balance =
+        token::balance_of(deps.as_ref(), dp_token.to_string(), holder.to_string())? + incoming;
+    let holder = address::canonicalize(deps.api, holder.as_str())?;
+    time_weight::update(deps.storage, &holder, env.block.time.seconds(), balance)?;
+    snapshot::store_deposit_amount(deps.storage, &holder, env.block.height, balance)?;
+
+    Ok(balance)
+}
+
+/// fails if `actual` falls short of the `minimum` the user asked for
+fn assert_minimum(minimum: Option<Uint256>, actual: Uint256) -> Result<(), ContractError> {
+    match minimum {
+        Some(minimum) if actual < minimum => {
+            Err(ContractError::SlippageExceeded { minimum, actual })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `amount` of DP held by the pool, paid to `recipient` as simulated
+struct Redemption {
+    recipient: CanonicalAddr,
+    amount: Uint256,
+    min_receive: Uint256,
+    simulation: simulation::Redeem,
+}
+
+/// burns the redeemed DP and withdraws their share tokens from the money market.
+/// recipients are paid once the stable coins arrive, see `settle_redemptions`
+fn redemption_msgs(
+    deps: DepsMut,
+    env: &Env,
+    config: &config::Config,
+    dp_token: String,
+    redemptions: Vec<Redemption>,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let mut amount = Uint256::zero();
+    let mut atoken_amount = Uint256::zero();
+    let mut payouts: Vec<settlement::Payout> = vec![];
+    let mut state = state::read(deps.storage)?;
+    for redemption in redemptions {
+        amount += redemption.amount;
+        atoken_amount += redemption.simulation.atoken_amount;
+        state.reserve = state.reserve - redemption.simulation.reserve_amount;
+        payouts.push(settlement::Payout {
+            recipient: redemption.recipient,
+            min_receive: redemption.min_receive,
+            reserve_amount: redemption.simulation.reserve_amount,
+            market_amount: redemption.simulation.market_amount,
+        });
+    }
+    state.total_redeemed += amount;
+    state::store(deps.storage, &state)?;
+
+    let mut msgs = vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: dp_token,
+        msg: to_binary(&Cw20ExecuteMsg::Burn {
+            amount: amount.into(),
+        })?,
+        funds: vec![],
+    }))];
+    // served from the reserve alone, nothing to wait for
+    if atoken_amount.is_zero() {
+        let (payout_msgs, _) = pay(deps, config, &payouts, Uint256::zero())?;
+        msgs.extend(payout_msgs.into_iter().map(SubMsg::new));
+        return Ok(msgs);
+    }
+
+    let balance_before = deps
+        .querier
+        .query_balance(env.contract.address.clone(), config.stable_denom.as_str())?;
+    settlement::store(
+        deps.storage,
+        &settlement::Settlement {
+            balance_before: balance_before.amount.into(),
+            payouts,
+        },
+    )?;
+
+    let mut market_msgs =
+        moneymarket::load(config).redeem_msgs(deps.as_ref(), atoken_amount.into())?;
+    let last = market_msgs
+        .pop()
+        .ok_or(ContractError::NoRedeemMessages {})?;
+    msgs.extend(market_msgs.into_iter().map(SubMsg::new));
+    msgs.push(SubMsg::reply_on_success(last, contract::REDEEM_REPLY_ID));
+
+    Ok(msgs)
+}
+
+/// splits `received` from the money market among `payouts` by their market amount and
+/// pays each recipient its share plus its reserve amount, less one transfer tax.
+/// whatever can't be paid out is added to the dust
+fn pay(
+    deps: DepsMut,
+    config: &config::Config,
+    payouts: &[settlement::Payout],
+    received: Uint256,
+) -> Result<(Vec<CosmosMsg>, Uint256), ContractError> {
+    let market_total = payouts
+        .iter()
+        .fold(Uint256::zero(), |acc, payout| acc + payout.market_amount);
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut distributed = Uint256::zero();
+    let mut dust = Uint256::zero();
+    for payout in payouts {
+        let share = if market_total.is_zero() {
+            Uint256::zero()
+        } else {
+            received.multiply_ratio(payout.market_amount, market_total)
+        };
+        distributed += share;
+
+        let payable = payout.reserve_amount + share;
+        let sent: Uint256 = deduct_tax(
+            deps.as_ref(),
+            coin(u128::from(payable), config.stable_denom.clone()),
+        )?
+        .amount
+        .into();
+        let tax = settlement::transfer_tax(deps.as_ref(), &config.stable_denom, sent)?;
+        dust += payable - sent - tax;
+        assert_minimum(Some(payout.min_receive), sent)?;
+
+        if !sent.is_zero() {
+            msgs.push(CosmosMsg::Bank(BankMsg::Send {
+                to_address: address::humanize(deps.api, &payout.recipient)?.to_string(),
+                amount: vec![coin(u128::from(sent), config.stable_denom.clone())],
+            }));
+        }
+    }
+    // rounding left over by the split
+    dust += received - distributed;
+
+    let mut state = state::read(deps.storage)?;
+    state.dust += dust;
+    state::store(deps.storage, &state)?;
+
+    Ok((msgs, dust))
+}
+
+pub fn settle_redemptions(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    let settlement =
+        settlement::read(deps.storage)?.ok_or(ContractError::NoPendingSettlement {})?;
+    settlement::remove(deps.storage);
+
+    let balance: Uint256 = deps
+        .querier
+        .query_balance(env.contract.address, config.stable_denom.as_str())?
+        .amount
+        .into();
+    let received = if balance > settlement.balance_before {
+        balance - settlement.balance_before
+    } else {
+        Uint256::zero()
+    };
+
+    let (msgs, dust) = pay(deps, &config, &settlement.payouts, received)?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "settle_redemption")
+        .add_attribute("received", received.to_string())
+        .add_attribute("dust", dust.to_string()))
+}
+
+pub fn process_redemptions(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    pause::assert_redeem_allowed(deps.storage)?;
+    let config = config::load(deps.storage)?;
+    let dp_token = config.dp_token_addr(deps.api)?.to_string();
+    let mut liquidity = moneymarket::load(&config).liquidity(deps.as_ref())?;
+    // each filled ticket draws on what the ones before it left
+    let mut holdings = simulation::holdings(deps.as_ref(), &env, &config)?;
+
+    let mut redemptions: Vec<Redemption> = vec![];
+    let mut refunds: Vec<CosmosMsg> = vec![];
+    for ticket in redemption_queue::front(deps.storage)? {
+        let simulation = simulation::redeem_from(deps.as_ref(), &config, &holdings, ticket.amount)?;
+        // a ticket the pool can no longer pay in full gets its DP back instead of a short payout
+        if simulation.stable_amount < ticket.min_receive {
+            redemption_queue::remove(deps.storage, &ticket)?;
+            refunds.push(refund_ticket(deps.branch(), &env, &dp_token, &ticket)?);
+            continue;
+        }
+        // stop at the first ticket the market cannot pay, so none is skipped
+        if simulation.market_amount > liquidity {
+            break;
+        }
+        liquidity = liquidity - simulation.market_amount;
+        holdings = holdings.after(ticket.amount, &simulation);
+
+        redemption_queue::remove(deps.storage, &ticket)?;
+        // min_receive was applied above against the same simulation the payout is built from
+        redemptions.push(Redemption {
+            recipient: ticket.recipient,
+            amount: ticket.amount,
+            min_receive: Uint256::zero(),
+            simulation,
+        });
+    }
+
+    let filled = redemptions.len();
+    let refunded = refunds.len();
+    let mut msgs: Vec<SubMsg> = vec![];
+    if filled > 0 {
+        snapshot::store_total_deposit_amount(
+            deps.storage,
+            env.block.height,
+            holdings.dp_total_supply,
+        )?;
+        msgs = redemption_msgs(deps.branch(), &env, &config, dp_token, redemptions)?;
+    }
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_messages(refunds)
+        .add_attribute("action", "process_redemptions")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("filled", filled.to_string())
+        .add_attribute("refunded", refunded.to_string())
+        .add_attribute(
+            "remaining",
+            redemption_queue::read(deps.storage)?.depth.to_string(),
+        ))
+}
+
+/// returns the DP of a ticket to its owner, who holds it again from now on
+fn refund_ticket(
+    mut deps: DepsMut,
+    env: &Env,
+    dp_token: &str,
+    ticket: &redemption_queue::Ticket,
+) -> Result<CosmosMsg, ContractError> {
+    let owner = address::humanize(deps.api, &ticket.owner)?;
+    sync_holder(deps.branch(), env, dp_token, &owner, ticket.amount)?;
+    time_weight::increase_total(deps.storage, env.block.time.seconds(), ticket.amount)?;
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: dp_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: owner.to_string(),
+            amount: ticket.amount.into(),
+        })?,
+        funds: vec![],
+    }))
 }
 
 pub fn earn(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    // calculate deduct(total_aust_amount * exchange_rate) - (total_dp_balance)
-    let config = config::read(deps.storage).unwrap();
-    if config.beneficiary != deps.api.addr_canonicalize(info.sender.as_str()).unwrap() {
+    pause::assert_earn_allowed(deps.storage)?;
+    let config = config::load(deps.storage)?;
+    let caller = address::canonicalize(deps.api, info.sender.as_str())?;
+    // without keeper mode, earn stays with the beneficiaries
+    if config.keeper.is_none() && !reward::is_beneficiary(&config, &caller) {
         return Err(ContractError::Unauthorized {
             action: "earn".to_string(),
-            expected: deps
-                .api
-                .addr_humanize(&config.beneficiary)
-                .unwrap()
-                .to_string(),
+            expected: address::humanize(deps.api, &config.beneficiary)?.to_string(),
             actual: info.sender.to_string(),
         });
     }
 
-    // assets
-    let epoch_state = anchor::epoch_state(deps.as_ref(), &config.moneymarket)?;
-    let atoken_balance = token::balance_of(
-        deps.as_ref(),
-        deps.api.addr_humanize(&config.atoken).unwrap().to_string(),
-        env.contract.address.to_string(),
+    let pays_bounty = reward::pays_bounty(&config, &caller);
+    let reward = reward::claimable(deps.as_ref(), &env, &config, pays_bounty)?;
+    if !reward.deficit.is_zero() {
+        return Err(ContractError::PoolInDeficit {
+            deficit: reward.deficit,
+        });
+    }
+
+    let mut state = state::read(deps.storage)?;
+    if let (true, Some(keeper)) = (pays_bounty, &config.keeper) {
+        let next_earn_at = state.last_earned_at + keeper.min_interval;
+        if env.block.time.seconds() < next_earn_at && reward.earnable < keeper.min_earnable {
+            return Err(ContractError::EarnNotReady {
+                next_earn_at,
+                min_earnable: keeper.min_earnable,
+            });
+        }
+    }
+    state.last_earned_at = env.block.time.seconds();
+    state::store(deps.storage, &state)?;
+
+    // the rewards are paid out of what actually arrives, see `distribute_reward`
+    let balance_before = deps
+        .querier
+        .query_balance(env.contract.address, config.stable_denom.as_str())?;
+    settlement::store_claim(
+        deps.storage,
+        &settlement::Claim {
+            balance_before: balance_before.amount.into(),
+            caller,
+            exchange_rate: reward.exchange_rate,
+            earnable: reward.earnable,
+            fee: reward.fee,
+            bounty: reward.bounty,
+        },
     )?;
-    let dp_total_supply = token::total_supply(
+
+    let mut market_msgs = moneymarket::load(&config).redeem_msgs(
         deps.as_ref(),
-        deps.api
-            .addr_humanize(&config.dp_token)
-            .unwrap()
-            .to_string(),
+        reward.earnable.div(reward.exchange_rate).into(),
     )?;
-
-    let pool_value_locked = Uint256::from(
-        deduct_tax(
-            deps.as_ref(),
-            Coin {
-                denom: config.stable_denom.clone(),
-                amount: (atoken_balance.mul(epoch_state.exchange_rate)).into(),
-            },
-        )?
-        .amount,
-    );
-    let earnable = pool_value_locked.sub(dp_total_supply);
-    let fee = earnable.div(Decimal256::from_str("5.0")?); // TODO: fix it (20%)
+    let last = market_msgs
+        .pop()
+        .ok_or(ContractError::NoRedeemMessages {})?;
+    let mut msgs: Vec<SubMsg> = market_msgs.into_iter().map(SubMsg::new).collect();
+    msgs.push(SubMsg::reply_on_success(last, contract::EARN_REPLY_ID));
 
     Ok(Response::new()
-        .add_messages(anchor::redeem_stable_msg(
-            deps.as_ref(),
-            &config.moneymarket,
-            &config.atoken,
-            earnable.div(epoch_state.exchange_rate).into(),
-        )?)
-        .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: deps
-                .api
-                .addr_humanize(&config.beneficiary)
-                .unwrap()
-                .to_string(),
-            amount: vec![deduct_tax(
-                deps.as_ref(),
-                Coin {
-                    denom: config.stable_denom.clone(),
-                    amount: earnable.sub(fee).into(),
-                },
-            )?],
-        }))
-        .add_message(CosmosMsg::Bank(BankMsg::Send {
-            to_address: deps
-                .api
-                .addr_humanize(&config.fee_collector)
-                .unwrap()
-                .to_string(),
+        .add_submessages(msgs)
+        .add_attribute("action", "claim_reward")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("amount", reward.amount().to_string())
+        .add_attribute("fee", reward.fee.to_string())
+        .add_attribute("bounty", reward.bounty.to_string()))
+}
+
+pub fn distribute_reward(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    let claim = settlement::read_claim(deps.storage)?.ok_or(ContractError::NoPendingClaim {})?;
+    settlement::remove_claim(deps.storage);
+
+    let balance: Uint256 = deps
+        .querier
+        .query_balance(env.contract.address, config.stable_denom.as_str())?
+        .amount
+        .into();
+    let received = if balance > claim.balance_before {
+        balance - claim.balance_before
+    } else {
+        Uint256::zero()
+    };
+
+    // a market paying out less than earnable shrinks every share, so the reserve never covers it
+    let (earned, fee, bounty) = if received < claim.earnable {
+        (
+            received,
+            claim.fee.multiply_ratio(received, claim.earnable),
+            claim.bounty.multiply_ratio(received, claim.earnable),
+        )
+    } else {
+        (claim.earnable, claim.fee, claim.bounty)
+    };
+    let amount = earned - fee - bounty;
+
+    let mut state = state::read(deps.storage)?;
+    state.total_yield_earned += earned;
+    state.total_fee += fee;
+    state.dust += received - earned;
+    state::store(deps.storage, &state)?;
+
+    let harvest_id = earn_history::next_id(deps.storage)?;
+    earn_history::store(
+        deps.storage,
+        &earn_history::Harvest {
+            id: harvest_id,
+            height: env.block.height,
+            time: env.block.time.seconds(),
+            exchange_rate: claim.exchange_rate,
+            earnable: earned,
+            amount,
+            fee,
+            bounty,
+            caller: claim.caller.clone(),
+        },
+    )?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let recipients = reward::distribute(&config, amount).into_iter().chain(vec![
+        (claim.caller, bounty),
+        (config.fee_collector.clone(), fee),
+    ]);
+    for (recipient, amount) in recipients {
+        if amount.is_zero() {
+            continue;
+        }
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: address::humanize(deps.api, &recipient)?.to_string(),
             amount: vec![deduct_tax(
                 deps.as_ref(),
                 Coin {
                     denom: config.stable_denom.clone(),
-                    amount: fee.into(),
+                    amount: amount.into(),
                 },
             )?],
-        }))
-        .add_attribute("action", "claim_reward")
-        .add_attribute("sender", info.sender.to_string())
-        .add_attribute("amount", earnable.sub(fee).to_string())
-        .add_attribute("fee", fee.to_string()))
+        }));
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "distribute_reward")
+        .add_attribute("received", received.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("fee", fee.to_string())
+        .add_attribute("bounty", bounty.to_string()))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn configure(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     beneficiary: Option<String>,
     fee_collector: Option<String>,
+    fee_rate: Option<Decimal256>,
+    pauser: Option<String>,
+    deposit_cap: Option<Uint256>,
+    deposit_cap_per_address: Option<Uint256>,
+    beneficiaries: Option<Vec<BeneficiaryMsg>>,
+    redemption_policy: Option<RedemptionPolicy>,
+    reserve_ratio: Option<Decimal256>,
+    queue_redemptions: Option<bool>,
 ) -> Result<Response, ContractError> {
-    let mut config = config::read(deps.storage).unwrap();
-    if config.owner != deps.api.addr_canonicalize(info.sender.as_str()).unwrap() {
+    let mut config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
         return Err(ContractError::Unauthorized {
             action: "configure".to_string(),
-            expected: deps.api.addr_humanize(&config.owner).unwrap().to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
             actual: info.sender.to_string(),
         });
     }
 
     if let Some(beneficiary) = beneficiary {
-        config.beneficiary = deps.api.addr_canonicalize(beneficiary.as_str()).unwrap();
+        config.beneficiary = address::canonicalize(deps.api, beneficiary.as_str())?;
     }
     if let Some(fee_collector) = fee_collector {
-        config.fee_collector = deps.api.addr_canonicalize(fee_collector.as_str()).unwrap();
+        config.fee_collector = address::canonicalize(deps.api, fee_collector.as_str())?;
+    }
+    if let Some(fee_rate) = fee_rate {
+        config::validate_fee_rate(fee_rate)?;
+        config.fee_rate = fee_rate;
+    }
+    if let Some(pauser) = pauser {
+        config.pauser = Some(address::canonicalize(deps.api, pauser.as_str())?);
+    }
+    if let Some(beneficiaries) = beneficiaries {
+        config.beneficiaries = beneficiaries
+            .iter()
+            .map(|b| {
+                Ok(config::Beneficiary {
+                    address: address::canonicalize(deps.api, b.address.as_str())?,
+                    weight: b.weight,
+                })
+            })
+            .collect::<Result<Vec<config::Beneficiary>, ContractError>>()?;
+        config::validate_beneficiaries(&config.beneficiaries)?;
+    }
+    // zero removes the cap
+    if let Some(deposit_cap) = deposit_cap {
+        config.deposit_cap = Some(deposit_cap).filter(|cap| !cap.is_zero());
+    }
+    if let Some(deposit_cap_per_address) = deposit_cap_per_address {
+        config.deposit_cap_per_address = Some(deposit_cap_per_address).filter(|cap| !cap.is_zero());
+    }
+    if let Some(redemption_policy) = redemption_policy {
+        config.redemption_policy = redemption_policy;
+    }
+    if let Some(reserve_ratio) = reserve_ratio {
+        config::validate_reserve_ratio(reserve_ratio)?;
+        config.reserve_ratio = reserve_ratio;
+    }
+    if let Some(queue_redemptions) = queue_redemptions {
+        config.queue_redemptions = queue_redemptions;
     }
     config::store(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+pub fn rebalance(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    // moves funds both ways, so either pause stops it
+    pause::assert_deposit_allowed(deps.storage)?;
+    pause::assert_redeem_allowed(deps.storage)?;
+    let config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "rebalance".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+    let rebalance = simulation::rebalance(deps.as_ref(), &config)?;
+
+    let market = moneymarket::load(&config);
+    let mut msgs: Vec<SubMsg> = vec![];
+    if !rebalance.deposit_amount.is_zero() {
+        let mut state = state::read(deps.storage)?;
+        state.reserve = state.reserve - rebalance.deposit_amount;
+        state::store(deps.storage, &state)?;
+
+        msgs.extend(
+            market
+                .deposit_msgs(deps.as_ref(), rebalance.deposit_amount.into())?
+                .into_iter()
+                .map(SubMsg::new),
+        );
+    }
+    if !rebalance.atoken_amount.is_zero() {
+        // the reserve is credited with what actually arrives, see `refill_reserve`
+        let balance_before = deps
+            .querier
+            .query_balance(env.contract.address, config.stable_denom.as_str())?;
+        settlement::store_refill(
+            deps.storage,
+            &settlement::Refill {
+                balance_before: balance_before.amount.into(),
+            },
+        )?;
+
+        let mut market_msgs = market.redeem_msgs(deps.as_ref(), rebalance.atoken_amount.into())?;
+        let last = market_msgs
+            .pop()
+            .ok_or(ContractError::NoRedeemMessages {})?;
+        msgs.extend(market_msgs.into_iter().map(SubMsg::new));
+        msgs.push(SubMsg::reply_on_success(last, contract::REFILL_REPLY_ID));
+    }
+
+    Ok(Response::new()
+        .add_submessages(msgs)
+        .add_attribute("action", "rebalance")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("deposit_amount", rebalance.deposit_amount.to_string())
+        .add_attribute("atoken_amount", rebalance.atoken_amount.to_string()))
+}
+
+pub fn refill_reserve(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    let refill = settlement::read_refill(deps.storage)?.ok_or(ContractError::NoPendingRefill {})?;
+    settlement::remove_refill(deps.storage);
+
+    let balance: Uint256 = deps
+        .querier
+        .query_balance(env.contract.address, config.stable_denom.as_str())?
+        .amount
+        .into();
+    let received = if balance > refill.balance_before {
+        balance - refill.balance_before
+    } else {
+        Uint256::zero()
+    };
+
+    let mut state = state::read(deps.storage)?;
+    state.reserve += received;
+    state::store(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "refill_reserve")
+        .add_attribute("received", received.to_string())
+        .add_attribute("reserve", state.reserve.to_string()))
+}
+
+pub fn configure_keeper(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    keeper: Option<KeeperMsg>,
+) -> Result<Response, ContractError> {
+    let mut config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "configure_keeper".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    if let Some(keeper) = &keeper {
+        config::validate_bounty_rate(keeper.bounty_rate)?;
+    }
+    config.keeper = keeper.map(|keeper| config::Keeper {
+        min_interval: keeper.min_interval,
+        min_earnable: keeper.min_earnable,
+        bounty_rate: keeper.bounty_rate,
+    });
+    config::store(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "configure_keeper")
+        .add_attribute("enabled", config.keeper.is_some().to_string()))
+}
+
+pub fn set_pause(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    deposit: Option<bool>,
+    redeem: Option<bool>,
+    earn: Option<bool>,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    let sender = address::canonicalize(deps.api, info.sender.as_str())?;
+    if sender != config.owner && Some(sender) != config.pauser {
+        return Err(ContractError::Unauthorized {
+            action: "set_pause".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    let mut pause = pause::read(deps.storage)?;
+    if let Some(deposit) = deposit {
+        pause.deposit = deposit;
+    }
+    if let Some(redeem) = redeem {
+        pause.redeem = redeem;
+    }
+    if let Some(earn) = earn {
+        pause.earn = earn;
+    }
+    pause::store(deps.storage, &pause)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("deposit", pause.deposit.to_string())
+        .add_attribute("redeem", pause.redeem.to_string())
+        .add_attribute("earn", pause.earn.to_string()))
+}
+
+pub fn clear_pauser(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "clear_pauser".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    let pauser = config.pauser.take().ok_or(ContractError::NoPauser {})?;
+    config::store(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "clear_pauser")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("pauser", address::humanize(deps.api, &pauser)?.to_string()))
+}
+
+pub fn transfer_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "transfer_ownership".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    config::store_pending_owner(
+        deps.storage,
+        &address::canonicalize(deps.api, new_owner.as_str())?,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_ownership")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("pending_owner", new_owner))
+}
+
+pub fn accept_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = config::load(deps.storage)?;
+    let pending_owner =
+        config::read_pending_owner(deps.storage)?.ok_or(ContractError::NoPendingOwner {})?;
+    if pending_owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "accept_ownership".to_string(),
+            expected: address::humanize(deps.api, &pending_owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    let previous_owner = address::humanize(deps.api, &config.owner)?;
+    config.owner = pending_owner;
+    config::store(deps.storage, &config)?;
+    config::remove_pending_owner(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("previous_owner", previous_owner.to_string())
+        .add_attribute("owner", info.sender.to_string()))
+}
+
+pub fn cancel_ownership_transfer(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "cancel_ownership_transfer".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+
+    let pending_owner =
+        config::read_pending_owner(deps.storage)?.ok_or(ContractError::NoPendingOwner {})?;
+    config::remove_pending_owner(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_ownership_transfer")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute(
+            "pending_owner",
+            address::humanize(deps.api, &pending_owner)?.to_string(),
+        ))
+}
+
+/// migrates the DP token contract, works only while the pool is its wasm admin
+pub fn migrate_dp_token(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "migrate_dp_token".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+    let dp_token = config.dp_token_addr(deps.api)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Migrate {
+            contract_addr: dp_token.to_string(),
+            new_code_id: code_id,
+            msg,
+        })
+        .add_attribute("action", "migrate_dp_token")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("code_id", code_id.to_string()))
+}
+
+/// records an existing DP token to adopt. cw20-base cannot change its minter, so the
+/// token's wasm admin migrates it to hand the minter over before [adopt_dp_token]
+pub fn propose_dp_token(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::AdoptionFundsNotExpected {});
+    }
+    config::store_pending_dp_token(
+        deps.storage,
+        &address::canonicalize(deps.api, address.as_str())?,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_dp_token")
+        .add_attribute("dp_token", address.to_string()))
+}
+
+/// takes over the proposed DP token once its minter is the pool.
+/// stable coins sent along are kept as reserve, and together with any share tokens
+/// already held they must cover the DP in circulation.
+/// the whole supply starts accruing time weight now, each holder's own record
+/// starts with their next deposit, redeem, transfer or `sync_balances`
+pub fn adopt_dp_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = config::load(deps.storage)?;
+    if config.owner != address::canonicalize(deps.api, info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {
+            action: "adopt_dp_token".to_string(),
+            expected: address::humanize(deps.api, &config.owner)?.to_string(),
+            actual: info.sender.to_string(),
+        });
+    }
+    let pending =
+        config::read_pending_dp_token(deps.storage)?.ok_or(ContractError::NoPendingDpToken {})?;
+    let address = address::humanize(deps.api, &pending)?;
+
+    let minter = dp_token::minter(deps.as_ref(), &address)?.unwrap_or_default();
+    if minter != env.contract.address.as_str() {
+        return Err(ContractError::DpTokenMinterMismatch {
+            expected: env.contract.address.to_string(),
+            actual: minter,
+        });
+    }
+
+    if info.funds.iter().any(|c| c.denom != config.stable_denom) {
+        return Err(ContractError::NotAllowOtherDenoms {
+            denom: config.stable_denom,
+        });
+    }
+    let received: Uint256 = info
+        .funds
+        .iter()
+        .map(|c| Uint256::from(c.amount))
+        .fold(Uint256::zero(), |acc, amount| acc + amount);
+
+    config.dp_token = pending;
+    config::store(deps.storage, &config)?;
+    config::remove_pending_dp_token(deps.storage);
+
+    let mut state = state::read(deps.storage)?;
+    state.reserve += received;
+    state::store(deps.storage, &state)?;
+
+    let assets = reward::assets(deps.as_ref(), &env, &config)?;
+    if !assets.deficit().is_zero() {
+        return Err(ContractError::MigratedAssetsShort {
+            dp_total_supply: assets.dp_total_supply,
+            pool_value_locked: assets.pool_value_locked,
+        });
+    }
+    snapshot::store_total_deposit_amount(deps.storage, env.block.height, assets.dp_total_supply)?;
+    time_weight::increase_total(
+        deps.storage,
+        env.block.time.seconds(),
+        assets.dp_total_supply,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "adopt_dp_token")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("dp_token", address.to_string())
+        .add_attribute("dp_total_supply", assets.dp_total_supply.to_string())
+        .add_attribute("pool_value_locked", assets.pool_value_locked.to_string()))
+}
+
 pub fn register_dp_token(
     deps: DepsMut,
     _env: Env,
     address: Addr,
 ) -> Result<Response, ContractError> {
-    let mut config = config::read(deps.storage).unwrap();
+    let mut config = config::load(deps.storage)?;
     if config.dp_token != CanonicalAddr::from(vec![]) {
         return Err(ContractError::Unauthorized {
             action: "register_dp_token".to_string(),
             expected: "<empty>".to_string(),
-            actual: deps
-                .api
-                .addr_humanize(&config.dp_token)
-                .unwrap()
-                .to_string(),
+            actual: config.dp_token_addr(deps.api)?.to_string(),
         });
     }
 
-    config.dp_token = deps.api.addr_canonicalize(address.as_str()).unwrap();
+    config.dp_token = address::canonicalize(deps.api, address.as_str())?;
     config::store(deps.storage, &config)?;
 
     Ok(Response::new().add_attribute("dp_token", address.to_string()))
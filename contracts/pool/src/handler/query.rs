@@ -1,105 +1,309 @@
 use cosmwasm_bignumber::{Decimal256, Uint256};
 use cosmwasm_std::*;
+use pylon_core::address;
+use pylon_core::pool_msg::{Beneficiary, Keeper};
 use pylon_core::pool_resp as resp;
-use pylon_utils::tax::deduct_tax;
 use pylon_utils::token;
-use std::ops::{Div, Mul, Sub};
-use std::str::FromStr;
 
+use crate::capacity;
 use crate::config;
-use crate::querier::anchor;
-
-pub fn deposit_amount(deps: Deps, _env: Env, owner: String) -> StdResult<Binary> {
-    let config: config::Config = config::read(deps.storage).unwrap();
-
-    to_binary(&resp::DepositAmountResponse {
-        amount: token::balance_of(
-            deps,
-            deps.api
-                .addr_humanize(&config.dp_token)
-                .unwrap()
-                .to_string(),
-            owner,
-        )?,
-    })
-}
-
-pub fn total_deposit_amount(deps: Deps, _env: Env) -> StdResult<Binary> {
-    let config: config::Config = config::read(deps.storage).unwrap();
-
-    to_binary(&resp::TotalDepositAmountResponse {
-        amount: token::total_supply(
-            deps,
-            deps.api
-                .addr_humanize(&config.dp_token)
-                .unwrap()
-                .to_string(),
-        )?,
-    })
-}
-
-pub fn config(deps: Deps, _env: Env) -> StdResult<Binary> {
-    let config: config::Config = config::read(deps.storage).unwrap();
-
-    to_binary(&resp::ConfigResponse {
-        beneficiary: deps
-            .api
-            .addr_humanize(&config.beneficiary)
-            .unwrap()
-            .to_string(),
-        fee_collector: deps
-            .api
-            .addr_humanize(&config.fee_collector)
-            .unwrap()
-            .to_string(),
-        moneymarket: deps
-            .api
-            .addr_humanize(&config.moneymarket)
-            .unwrap()
-            .to_string(),
+use crate::earn_history;
+use crate::error::ContractError;
+use crate::pause;
+use crate::redemption_queue;
+use crate::reward;
+use crate::simulation;
+use crate::snapshot;
+use crate::state;
+use crate::time_weight;
+
+pub fn deposit_amount(deps: Deps, _env: Env, owner: String) -> Result<Binary, ContractError> {
+    let config: config::Config = config::load(deps.storage)?;
+
+    Ok(to_binary(&resp::DepositAmountResponse {
+        amount: token::balance_of(deps, config.dp_token_addr(deps.api)?.to_string(), owner)?,
+    })?)
+}
+
+pub fn total_deposit_amount(deps: Deps, _env: Env) -> Result<Binary, ContractError> {
+    let config: config::Config = config::load(deps.storage)?;
+
+    Ok(to_binary(&resp::TotalDepositAmountResponse {
+        amount: token::total_supply(deps, config.dp_token_addr(deps.api)?.to_string())?,
+    })?)
+}
+
+pub fn deposit_amount_at(
+    deps: Deps,
+    _env: Env,
+    owner: String,
+    height: u64,
+) -> Result<Binary, ContractError> {
+    let owner = address::canonicalize(deps.api, owner.as_str())?;
+
+    Ok(to_binary(&resp::DepositAmountResponse {
+        amount: snapshot::deposit_amount_at(deps.storage, &owner, height)?,
+    })?)
+}
+
+pub fn total_deposit_amount_at(
+    deps: Deps,
+    _env: Env,
+    height: u64,
+) -> Result<Binary, ContractError> {
+    Ok(to_binary(&resp::TotalDepositAmountResponse {
+        amount: snapshot::total_deposit_amount_at(deps.storage, height)?,
+    })?)
+}
+
+pub fn config(deps: Deps, _env: Env) -> Result<Binary, ContractError> {
+    let config: config::Config = config::load(deps.storage)?;
+
+    let dp_token = config.dp_token_addr(deps.api)?;
+    let pending_owner = config::read_pending_owner(deps.storage)?
+        .map(|owner| address::humanize(deps.api, &owner))
+        .transpose()?;
+
+    Ok(to_binary(&resp::ConfigResponse {
+        owner: address::humanize(deps.api, &config.owner)?.to_string(),
+        pending_owner: pending_owner.map(|owner| owner.to_string()),
+        pauser: config
+            .pauser
+            .map(|pauser| address::humanize(deps.api, &pauser))
+            .transpose()?
+            .map(|pauser| pauser.to_string()),
+        beneficiary: address::humanize(deps.api, &config.beneficiary)?.to_string(),
+        beneficiaries: config
+            .beneficiaries
+            .iter()
+            .map(|b| {
+                Ok(Beneficiary {
+                    address: address::humanize(deps.api, &b.address)?.to_string(),
+                    weight: b.weight,
+                })
+            })
+            .collect::<Result<Vec<Beneficiary>, ContractError>>()?,
+        fee_collector: address::humanize(deps.api, &config.fee_collector)?.to_string(),
+        fee_rate: config.fee_rate,
+        keeper: config.keeper.map(|keeper| Keeper {
+            min_interval: keeper.min_interval,
+            min_earnable: keeper.min_earnable,
+            bounty_rate: keeper.bounty_rate,
+        }),
+        moneymarket: address::humanize(deps.api, &config.moneymarket)?.to_string(),
+        moneymarket_kind: config.moneymarket_kind,
         stable_denom: config.stable_denom,
-        anchor_token: deps.api.addr_humanize(&config.atoken).unwrap().to_string(),
-        dp_token: deps
-            .api
-            .addr_humanize(&config.dp_token)
-            .unwrap()
-            .to_string(),
-    })
-}
-
-pub fn claimable_reward(deps: Deps, env: Env) -> StdResult<Binary> {
-    let config = config::read(deps.storage).unwrap();
-
-    // assets
-    let epoch_state = anchor::epoch_state(deps, &config.moneymarket)?;
-    let atoken_balance = token::balance_of(
-        deps,
-        deps.api.addr_humanize(&config.atoken).unwrap().to_string(),
-        env.contract.address.to_string(),
-    )?;
-    let dp_total_supply = token::total_supply(
-        deps,
-        deps.api
-            .addr_humanize(&config.dp_token)
-            .unwrap()
-            .to_string(),
-    )?;
-
-    let pool_value_locked = Uint256::from(
-        deduct_tax(
-            deps,
-            Coin {
-                denom: config.stable_denom,
-                amount: (atoken_balance.mul(epoch_state.exchange_rate)).into(),
-            },
-        )?
-        .amount,
-    );
-    let earnable = pool_value_locked.sub(dp_total_supply);
-    let fee = earnable.div(Decimal256::from_str("5.0")?); // TODO: fix it (20%)
-
-    to_binary(&resp::ClaimableRewardResponse {
-        amount: earnable.sub(fee),
-        fee,
-    })
+        anchor_token: address::humanize(deps.api, &config.atoken)?.to_string(),
+        dp_token: dp_token.to_string(),
+        deposit_cap: config.deposit_cap,
+        deposit_cap_per_address: config.deposit_cap_per_address,
+        redemption_policy: config.redemption_policy,
+        reserve_ratio: config.reserve_ratio,
+        queue_redemptions: config.queue_redemptions,
+    })?)
+}
+
+pub fn claimable_reward(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    // amounts as earned by the owner or a beneficiary, the bounty is what a keeper would take out of them
+    let reward = reward::claimable(deps, &env, &config, false)?;
+    let bounty = reward::claimable(deps, &env, &config, true)?.bounty;
+
+    let beneficiaries = reward::distribute(&config, reward.amount())
+        .into_iter()
+        .map(|(beneficiary, amount)| {
+            Ok(resp::BeneficiaryReward {
+                address: address::humanize(deps.api, &beneficiary)?.to_string(),
+                amount,
+            })
+        })
+        .collect::<Result<Vec<resp::BeneficiaryReward>, ContractError>>()?;
+
+    Ok(to_binary(&resp::ClaimableRewardResponse {
+        amount: reward.amount(),
+        fee: reward.fee,
+        bounty,
+        beneficiaries,
+    })?)
+}
+
+pub fn pause_info(deps: Deps, _env: Env) -> Result<Binary, ContractError> {
+    let pause = pause::read(deps.storage)?;
+
+    Ok(to_binary(&resp::PauseInfoResponse {
+        deposit: pause.deposit,
+        redeem: pause.redeem,
+        earn: pause.earn,
+    })?)
+}
+
+pub fn deposit_capacity(deps: Deps, _env: Env, owner: String) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    let capacity = capacity::of(deps, &config, owner)?;
+
+    Ok(to_binary(&resp::DepositCapacityResponse {
+        total: capacity.total,
+        address: capacity.address,
+        remaining: capacity.remaining(),
+    })?)
+}
+
+pub fn deposit_balance_seconds(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    at: Option<u64>,
+) -> Result<Binary, ContractError> {
+    let owner = address::canonicalize(deps.api, owner.as_str())?;
+    let at = at.unwrap_or_else(|| env.block.time.seconds());
+
+    Ok(to_binary(&resp::DepositBalanceSecondsResponse {
+        amount: time_weight::accumulated_of(deps.storage, &owner, at)?,
+        total: time_weight::total_accumulated(deps.storage, at)?,
+    })?)
+}
+
+pub fn deposit_share(
+    deps: Deps,
+    _env: Env,
+    owner: String,
+    start: u64,
+    end: u64,
+) -> Result<Binary, ContractError> {
+    if start > end {
+        return Err(ContractError::InvalidSharePeriod { start, end });
+    }
+    let owner = address::canonicalize(deps.api, owner.as_str())?;
+
+    let amount = time_weight::accumulated_of(deps.storage, &owner, end)?
+        - time_weight::accumulated_of(deps.storage, &owner, start)?;
+    let total = time_weight::total_accumulated(deps.storage, end)?
+        - time_weight::total_accumulated(deps.storage, start)?;
+
+    Ok(to_binary(&resp::DepositShareResponse {
+        amount,
+        total,
+        share: if total.is_zero() {
+            Decimal256::zero()
+        } else {
+            Decimal256::from_ratio(amount, total)
+        },
+    })?)
+}
+
+pub fn pool_info(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    let assets = reward::assets(deps, &env, &config)?;
+    let state = state::read(deps.storage)?;
+
+    Ok(to_binary(&resp::PoolInfoResponse {
+        atoken_balance: assets.atoken_balance,
+        reserve: assets.reserve,
+        dust: state.dust,
+        exchange_rate: assets.exchange_rate,
+        pool_value_locked: assets.pool_value_locked,
+        dp_total_supply: assets.dp_total_supply,
+        total_deposited: state.total_deposited,
+        total_redeemed: state.total_redeemed,
+        total_yield_earned: state.total_yield_earned,
+        total_fee: state.total_fee,
+    })?)
+}
+
+pub fn simulate_deposit(deps: Deps, _env: Env, amount: Uint256) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    let simulation = simulation::deposit(deps, &config, amount)?;
+
+    Ok(to_binary(&resp::SimulateDepositResponse {
+        dp_mint_amount: simulation.dp_mint_amount,
+        tax: simulation.tax,
+        reserve_amount: simulation.reserve_amount,
+        atoken_amount: simulation.atoken_amount,
+    })?)
+}
+
+pub fn simulate_redeem(deps: Deps, env: Env, amount: Uint256) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    let simulation = simulation::redeem(deps, &env, &config, amount)?;
+
+    Ok(to_binary(&resp::SimulateRedeemResponse {
+        stable_amount: simulation.stable_amount,
+        tax: simulation.tax,
+        haircut: simulation.haircut,
+        reserve_amount: simulation.reserve_amount,
+        atoken_amount: simulation.atoken_amount,
+    })?)
+}
+
+pub fn earn_history(
+    deps: Deps,
+    _env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let history = earn_history::read(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|harvest| {
+            Ok(resp::HarvestResponse {
+                id: harvest.id,
+                height: harvest.height,
+                time: harvest.time,
+                exchange_rate: harvest.exchange_rate,
+                earnable: harvest.earnable,
+                amount: harvest.amount,
+                fee: harvest.fee,
+                bounty: harvest.bounty,
+                caller: address::humanize(deps.api, &harvest.caller)?.to_string(),
+            })
+        })
+        .collect::<Result<Vec<resp::HarvestResponse>, ContractError>>()?;
+
+    Ok(to_binary(&resp::EarnHistoryResponse { history })?)
+}
+
+pub fn solvency(deps: Deps, env: Env) -> Result<Binary, ContractError> {
+    let config = config::load(deps.storage)?;
+    let assets = reward::assets(deps, &env, &config)?;
+
+    Ok(to_binary(&resp::SolvencyResponse {
+        pool_value_locked: assets.pool_value_locked,
+        dp_total_supply: assets.dp_total_supply,
+        deficit: assets.deficit(),
+        redemption_policy: config.redemption_policy,
+    })?)
+}
+
+pub fn redemption_tickets(
+    deps: Deps,
+    _env: Env,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Binary, ContractError> {
+    let owner = address::canonicalize(deps.api, owner.as_str())?;
+    let tickets = redemption_queue::tickets_of(deps.storage, &owner, start_after, limit)?
+        .into_iter()
+        .map(|ticket| {
+            Ok(resp::RedemptionTicketResponse {
+                id: ticket.id,
+                owner: address::humanize(deps.api, &ticket.owner)?.to_string(),
+                recipient: address::humanize(deps.api, &ticket.recipient)?.to_string(),
+                amount: ticket.amount,
+                min_receive: ticket.min_receive,
+                created_at: ticket.created_at,
+            })
+        })
+        .collect::<Result<Vec<resp::RedemptionTicketResponse>, ContractError>>()?;
+
+    Ok(to_binary(&resp::RedemptionTicketsResponse { tickets })?)
+}
+
+pub fn redemption_queue(deps: Deps, _env: Env) -> Result<Binary, ContractError> {
+    let queue = redemption_queue::read(deps.storage)?;
+
+    Ok(to_binary(&resp::RedemptionQueueResponse {
+        depth: queue.depth,
+        pending_amount: queue.pending_amount,
+        next_ticket: redemption_queue::first(deps.storage)?.map(|ticket| ticket.id),
+    })?)
 }
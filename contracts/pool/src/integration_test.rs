@@ -0,0 +1,1099 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{
+    coins, to_binary, Addr, Api, Binary, BlockInfo, CanonicalAddr, Decimal, Empty,
+    RecoverPubkeyError, StdError, StdResult, Storage, Uint128, VerificationError,
+};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_multi_test::{
+    App, AppBuilder, AppResponse, BankKeeper, ContractWrapper, CustomHandler, Executor,
+};
+use pylon_core::dp_token_msg::MigrateMsg as DpTokenMigrateMsg;
+use pylon_core::pool_msg::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MoneyMarketKind, QueryMsg, RedemptionPolicy,
+};
+use pylon_core::pool_resp::{
+    ClaimableRewardResponse, ConfigResponse, DepositAmountResponse, DepositShareResponse,
+    PoolInfoResponse, RedemptionQueueResponse, RedemptionTicketsResponse, SolvencyResponse,
+    TotalDepositAmountResponse,
+};
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper};
+
+use crate::contract;
+use crate::mock_anchor;
+
+const STABLE_DENOM: &str = "uusd";
+const OWNER: &str = "owner";
+const BENEFICIARY: &str = "beneficiary";
+const FEE_COLLECTOR: &str = "fee-collector";
+const USER: &str = "user";
+const BORROWER: &str = "borrower";
+
+type TerraApp = App<Empty, TerraQueryWrapper>;
+
+/// multi-test names contracts `Contract #N`, which `MockApi` refuses to validate.
+/// Canonical form is the raw address bytes so every address round trips unchanged.
+#[derive(Default)]
+struct PlainApi {
+    mock: MockApi,
+}
+
+impl Api for PlainApi {
+    fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+        self.addr_canonicalize(human)?;
+        Ok(Addr::unchecked(human))
+    }
+
+    fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+        if human.is_empty() {
+            return Err(StdError::generic_err("Invalid input: empty address"));
+        }
+        Ok(CanonicalAddr::from(human.as_bytes()))
+    }
+
+    fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+        String::from_utf8(canonical.to_vec())
+            .map(Addr::unchecked)
+            .map_err(StdError::invalid_utf8)
+    }
+
+    fn secp256k1_verify(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.mock
+            .secp256k1_verify(message_hash, signature, public_key)
+    }
+
+    fn secp256k1_recover_pubkey(
+        &self,
+        message_hash: &[u8],
+        signature: &[u8],
+        recovery_param: u8,
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        self.mock
+            .secp256k1_recover_pubkey(message_hash, signature, recovery_param)
+    }
+
+    fn ed25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        self.mock.ed25519_verify(message, signature, public_key)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        self.mock
+            .ed25519_batch_verify(messages, signatures, public_keys)
+    }
+
+    fn debug(&self, message: &str) {
+        self.mock.debug(message)
+    }
+}
+
+/// answers treasury queries with zero tax, the multi-test bank does not charge any
+struct NoTax;
+
+impl CustomHandler<Empty, TerraQueryWrapper> for NoTax {
+    fn execute(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _block: &BlockInfo,
+        sender: Addr,
+        msg: Empty,
+    ) -> AnyResult<AppResponse> {
+        panic!("testing: unexpected custom msg {:?} from {}", msg, sender)
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _block: &BlockInfo,
+        request: TerraQueryWrapper,
+    ) -> AnyResult<Binary> {
+        Ok(match request.query_data {
+            TerraQuery::TaxRate {} => to_binary(&TaxRateResponse {
+                rate: Decimal::zero(),
+            })?,
+            TerraQuery::TaxCap { .. } => to_binary(&TaxCapResponse {
+                cap: Uint128::zero(),
+            })?,
+            query => panic!("testing: unsupported terra query {:?}", query),
+        })
+    }
+}
+
+/// pool wired to a mock anchor market, real cw20 aUST and DP tokens
+struct Suite {
+    app: TerraApp,
+    token_code_id: u64,
+    dp_code_id: u64,
+    pool_code_id: u64,
+    market: Addr,
+    atoken: Addr,
+    pool: Addr,
+    dp_token: Addr,
+}
+
+impl Suite {
+    fn new(exchange_rate: Decimal256) -> Suite {
+        let mut app = AppBuilder::new()
+            .with_api(PlainApi::default())
+            .with_bank(BankKeeper::new())
+            .with_storage(MockStorage::new())
+            .with_custom(NoTax)
+            .build();
+
+        let token_code_id = app.store_code(Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        )));
+        let dp_code_id = app.store_code(Box::new(
+            ContractWrapper::new(
+                pylon_core_dp_token::contract::execute,
+                pylon_core_dp_token::contract::instantiate,
+                pylon_core_dp_token::contract::query,
+            )
+            .with_migrate(pylon_core_dp_token::contract::migrate),
+        ));
+        let market_code_id = app.store_code(Box::new(ContractWrapper::new(
+            mock_anchor::execute,
+            mock_anchor::instantiate,
+            mock_anchor::query,
+        )));
+        let pool_code_id = app.store_code(Box::new(
+            ContractWrapper::new(contract::execute, contract::instantiate, contract::query)
+                .with_reply(contract::reply),
+        ));
+
+        // market liquidity backing the yield
+        app.init_bank_balance(
+            &Addr::unchecked("market-reserve"),
+            coins(1_000_000_000, STABLE_DENOM),
+        )
+        .unwrap();
+        app.init_bank_balance(&Addr::unchecked(USER), coins(10_000_000, STABLE_DENOM))
+            .unwrap();
+
+        let market = app
+            .instantiate_contract(
+                market_code_id,
+                Addr::unchecked(OWNER),
+                &mock_anchor::InstantiateMsg {
+                    stable_denom: STABLE_DENOM.to_string(),
+                    exchange_rate,
+                },
+                &[],
+                "anchor market",
+                None,
+            )
+            .unwrap();
+        let atoken = app
+            .instantiate_contract(
+                token_code_id,
+                Addr::unchecked(OWNER),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Anchor Terra USD".to_string(),
+                    symbol: "aUST".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![],
+                    mint: Some(MinterResponse {
+                        minter: market.to_string(),
+                        cap: None,
+                    }),
+                    marketing: None,
+                },
+                &[],
+                "aUST",
+                None,
+            )
+            .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            market.clone(),
+            &mock_anchor::ExecuteMsg::RegisterAterra {
+                aterra_contract: atoken.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        app.send_tokens(
+            Addr::unchecked("market-reserve"),
+            market.clone(),
+            &coins(1_000_000_000, STABLE_DENOM),
+        )
+        .unwrap();
+
+        let pool = app
+            .instantiate_contract(
+                pool_code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    pool_name: "integration".to_string(),
+                    beneficiary: BENEFICIARY.to_string(),
+                    fee_collector: FEE_COLLECTOR.to_string(),
+                    fee_rate: Decimal256::percent(20),
+                    moneymarket: market.to_string(),
+                    moneymarket_kind: MoneyMarketKind::Anchor,
+                    dp_code_id: Some(dp_code_id),
+                    dp_token: None,
+                    dp_token_name: None,
+                    dp_token_symbol: None,
+                    dp_token_marketing: None,
+                    dp_token_label: None,
+                    dp_token_admin: None,
+                },
+                &[],
+                "pool",
+                None,
+            )
+            .unwrap();
+        let config: ConfigResponse = app
+            .wrap()
+            .query_wasm_smart(pool.clone(), &QueryMsg::Config {})
+            .unwrap();
+        let dp_token = Addr::unchecked(config.dp_token);
+
+        Suite {
+            app,
+            token_code_id,
+            dp_code_id,
+            pool_code_id,
+            market,
+            atoken,
+            pool,
+            dp_token,
+        }
+    }
+
+    fn set_exchange_rate(&mut self, exchange_rate: Decimal256) {
+        self.app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                self.market.clone(),
+                &mock_anchor::ExecuteMsg::SetExchangeRate { exchange_rate },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn execute(&mut self, sender: &str, msg: &ExecuteMsg) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.pool.clone(), msg, &[])
+    }
+
+    fn configure(
+        &mut self,
+        redemption_policy: Option<RedemptionPolicy>,
+        reserve_ratio: Option<Decimal256>,
+        queue_redemptions: Option<bool>,
+    ) {
+        self.execute(
+            OWNER,
+            &ExecuteMsg::Configure {
+                beneficiary: None,
+                fee_collector: None,
+                fee_rate: None,
+                pauser: None,
+                deposit_cap: None,
+                deposit_cap_per_address: None,
+                beneficiaries: None,
+                redemption_policy,
+                reserve_ratio,
+                queue_redemptions,
+            },
+        )
+        .unwrap();
+    }
+
+    fn deposit(&mut self, sender: &str, amount: u128) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.pool.clone(),
+            &ExecuteMsg::Deposit {
+                recipient: None,
+                min_mint: None,
+            },
+            &coins(amount, STABLE_DENOM),
+        )
+    }
+
+    fn redeem(&mut self, sender: &str, amount: u128) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(sender),
+            self.dp_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: self.pool.to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&Cw20HookMsg::Redeem {
+                    recipient: None,
+                    min_receive: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+    }
+
+    fn earn(&mut self, sender: &str) -> AnyResult<AppResponse> {
+        self.execute(sender, &ExecuteMsg::Earn {})
+    }
+
+    fn stable_balance(&self, address: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(address, STABLE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    fn token_balance(&self, token: &Addr, address: &str) -> u128 {
+        let resp: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                token.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        resp.balance.u128()
+    }
+
+    fn query<T: serde::de::DeserializeOwned>(&self, msg: &QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.pool.clone(), msg)
+            .unwrap()
+    }
+}
+
+#[test]
+fn instantiate_registers_dp_token() {
+    let suite = Suite::new(Decimal256::one());
+
+    let config: ConfigResponse = suite.query(&QueryMsg::Config {});
+    assert_eq!(config.stable_denom, STABLE_DENOM);
+    assert_eq!(config.anchor_token, suite.atoken.to_string());
+
+    let minter: Option<MinterResponse> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(suite.dp_token.clone(), &Cw20QueryMsg::Minter {})
+        .unwrap();
+    assert_eq!(minter.unwrap().minter, suite.pool.to_string());
+}
+
+#[test]
+fn deposit_earn_redeem() {
+    let mut suite = Suite::new(Decimal256::one());
+
+    suite.deposit(USER, 1_000_000).unwrap();
+    assert_eq!(suite.token_balance(&suite.dp_token, USER), 1_000_000);
+    assert_eq!(
+        suite.token_balance(&suite.atoken, suite.pool.as_str()),
+        1_000_000
+    );
+    let resp: DepositAmountResponse = suite.query(&QueryMsg::DepositAmountOf {
+        owner: USER.to_string(),
+    });
+    assert_eq!(resp.amount, Uint256::from(1_000_000u64));
+
+    // 25% yield accrued in the market
+    suite.set_exchange_rate(Decimal256::from_ratio(5, 4));
+    let reward: ClaimableRewardResponse = suite.query(&QueryMsg::ClaimableReward {});
+    assert_eq!(reward.amount, Uint256::from(200_000u64));
+    assert_eq!(reward.fee, Uint256::from(50_000u64));
+
+    suite.earn(BENEFICIARY).unwrap();
+    assert_eq!(suite.stable_balance(BENEFICIARY), 200_000);
+    assert_eq!(suite.stable_balance(FEE_COLLECTOR), 50_000);
+    assert_eq!(
+        suite.token_balance(&suite.atoken, suite.pool.as_str()),
+        800_000
+    );
+    let reward: ClaimableRewardResponse = suite.query(&QueryMsg::ClaimableReward {});
+    assert_eq!(reward.amount, Uint256::zero());
+
+    suite.redeem(USER, 1_000_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 10_000_000);
+    assert_eq!(suite.token_balance(&suite.dp_token, USER), 0);
+    assert_eq!(suite.token_balance(&suite.atoken, suite.pool.as_str()), 0);
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 0);
+    let resp: TotalDepositAmountResponse = suite.query(&QueryMsg::TotalDepositAmount {});
+    assert_eq!(resp.amount, Uint256::zero());
+}
+
+#[test]
+fn pool_info_tracks_lifetime_totals() {
+    let mut suite = Suite::new(Decimal256::one());
+
+    suite.deposit(USER, 1_000_000).unwrap();
+    suite.set_exchange_rate(Decimal256::from_ratio(5, 4));
+    suite.earn(BENEFICIARY).unwrap();
+    suite.redeem(USER, 400_000).unwrap();
+
+    let info: PoolInfoResponse = suite.query(&QueryMsg::PoolInfo {});
+    assert_eq!(
+        info,
+        PoolInfoResponse {
+            atoken_balance: Uint256::from(480_000u64),
+            reserve: Uint256::zero(),
+            dust: Uint256::zero(),
+            exchange_rate: Decimal256::from_ratio(5, 4),
+            pool_value_locked: Uint256::from(600_000u64),
+            dp_total_supply: Uint256::from(600_000u64),
+            total_deposited: Uint256::from(1_000_000u64),
+            total_redeemed: Uint256::from(400_000u64),
+            total_yield_earned: Uint256::from(250_000u64),
+            total_fee: Uint256::from(50_000u64),
+        }
+    );
+}
+
+#[test]
+fn redeem_rejects_other_tokens() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.deposit(USER, 1_000_000).unwrap();
+
+    // aUST minted to a user cannot be redeemed through the pool
+    let atoken = suite.atoken.clone();
+    suite
+        .app
+        .execute_contract(
+            suite.market.clone(),
+            atoken.clone(),
+            &Cw20ExecuteMsg::Mint {
+                recipient: USER.to_string(),
+                amount: Uint128::from(1_000u64),
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            atoken,
+            &Cw20ExecuteMsg::Send {
+                contract: suite.pool.to_string(),
+                amount: Uint128::from(1_000u64),
+                msg: to_binary(&Cw20HookMsg::Redeem {
+                    recipient: None,
+                    min_receive: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+    suite.redeem(USER, 1_000_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 10_000_000);
+}
+
+#[test]
+fn deficit_blocks_earn_and_haircuts_pro_rata() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.deposit(USER, 1_000_000).unwrap();
+
+    // the market lost 20% of its value
+    suite.set_exchange_rate(Decimal256::percent(80));
+    let solvency: SolvencyResponse = suite.query(&QueryMsg::Solvency {});
+    assert_eq!(solvency.pool_value_locked, Uint256::from(800_000u64));
+    assert_eq!(solvency.deficit, Uint256::from(200_000u64));
+    assert_eq!(solvency.redemption_policy, RedemptionPolicy::FirstCome);
+    let reward: ClaimableRewardResponse = suite.query(&QueryMsg::ClaimableReward {});
+    assert_eq!(reward.amount, Uint256::zero());
+    suite.earn(BENEFICIARY).unwrap_err();
+
+    suite.configure(Some(RedemptionPolicy::ProRata), None, None);
+
+    // half of the DP takes half of the loss
+    suite.redeem(USER, 500_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_400_000);
+    let solvency: SolvencyResponse = suite.query(&QueryMsg::Solvency {});
+    assert_eq!(solvency.pool_value_locked, Uint256::from(400_000u64));
+    assert_eq!(solvency.dp_total_supply, Uint256::from(500_000u64));
+    assert_eq!(solvency.deficit, Uint256::from(100_000u64));
+}
+
+#[test]
+fn reserve_serves_redemptions_first() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.configure(None, Some(Decimal256::percent(10)), None);
+
+    suite.deposit(USER, 1_000_000).unwrap();
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 100_000);
+    assert_eq!(
+        suite.token_balance(&suite.atoken, suite.pool.as_str()),
+        900_000
+    );
+
+    // paid out of the reserve without touching the market
+    suite.redeem(USER, 50_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_050_000);
+    assert_eq!(
+        suite.token_balance(&suite.atoken, suite.pool.as_str()),
+        900_000
+    );
+
+    // the reserve runs dry and the market covers the rest
+    suite.redeem(USER, 200_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_250_000);
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 0);
+    assert_eq!(
+        suite.token_balance(&suite.atoken, suite.pool.as_str()),
+        750_000
+    );
+
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::Rebalance {})
+        .unwrap_err();
+    let pause = |deposit: bool| ExecuteMsg::SetPause {
+        deposit: Some(deposit),
+        redeem: None,
+        earn: None,
+    };
+    suite.execute(OWNER, &pause(true)).unwrap();
+    suite.execute(OWNER, &ExecuteMsg::Rebalance {}).unwrap_err();
+    suite.execute(OWNER, &pause(false)).unwrap();
+
+    // credited with the UST the market actually paid
+    let resp = suite.execute(OWNER, &ExecuteMsg::Rebalance {}).unwrap();
+    assert!(resp.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "action" && attr.value == "refill_reserve")));
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 75_000);
+    let info: PoolInfoResponse = suite.query(&QueryMsg::PoolInfo {});
+    assert_eq!(info.reserve, Uint256::from(75_000u64));
+    assert_eq!(info.atoken_balance, Uint256::from(675_000u64));
+    assert_eq!(info.pool_value_locked, Uint256::from(750_000u64));
+
+    // dropping the target sends the reserve back to the market
+    suite.configure(None, Some(Decimal256::zero()), None);
+    suite.execute(OWNER, &ExecuteMsg::Rebalance {}).unwrap();
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 0);
+    let info: PoolInfoResponse = suite.query(&QueryMsg::PoolInfo {});
+    assert_eq!(info.reserve, Uint256::zero());
+    assert_eq!(info.atoken_balance, Uint256::from(750_000u64));
+}
+
+#[test]
+fn queue_redemptions_until_market_is_liquid() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.configure(None, None, Some(true));
+    suite.deposit(USER, 1_000_000).unwrap();
+
+    // borrowers took every UST the market had
+    let liquidity = suite.stable_balance(suite.market.as_str());
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            suite.market.clone(),
+            &mock_anchor::ExecuteMsg::Lend {
+                borrower: BORROWER.to_string(),
+                amount: Uint128::from(liquidity),
+            },
+            &[],
+        )
+        .unwrap();
+
+    suite.redeem(USER, 300_000).unwrap();
+    suite.redeem(USER, 200_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_000_000);
+    assert_eq!(suite.token_balance(&suite.dp_token, USER), 500_000);
+
+    let resp: RedemptionTicketsResponse = suite.query(&QueryMsg::RedemptionTicketsOf {
+        owner: USER.to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(
+        resp.tickets
+            .iter()
+            .map(|ticket| (ticket.id, ticket.amount))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, Uint256::from(300_000u64)),
+            (2, Uint256::from(200_000u64))
+        ]
+    );
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(
+        queue,
+        RedemptionQueueResponse {
+            depth: 2,
+            pending_amount: Uint256::from(500_000u64),
+            next_ticket: Some(1),
+        }
+    );
+
+    // nothing to fill yet
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::ProcessRedemptions {})
+        .unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_000_000);
+
+    // repayments only cover the first ticket, the second keeps its place
+    let market = suite.market.clone();
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(BORROWER),
+            market.clone(),
+            &coins(400_000, STABLE_DENOM),
+        )
+        .unwrap();
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::ProcessRedemptions {})
+        .unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_300_000);
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(queue.depth, 1);
+    assert_eq!(queue.next_ticket, Some(2));
+    let resp: TotalDepositAmountResponse = suite.query(&QueryMsg::TotalDepositAmount {});
+    assert_eq!(resp.amount, Uint256::from(700_000u64));
+
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(BORROWER),
+            market,
+            &coins(100_000, STABLE_DENOM),
+        )
+        .unwrap();
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::ProcessRedemptions {})
+        .unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_500_000);
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(queue.depth, 0);
+    assert_eq!(queue.pending_amount, Uint256::zero());
+}
+
+#[test]
+fn queued_redemptions_share_the_reserve() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.configure(None, Some(Decimal256::percent(10)), Some(true));
+    suite.deposit(USER, 1_000_000).unwrap();
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 100_000);
+
+    // borrowers took every UST the market had
+    let liquidity = suite.stable_balance(suite.market.as_str());
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            suite.market.clone(),
+            &mock_anchor::ExecuteMsg::Lend {
+                borrower: BORROWER.to_string(),
+                amount: Uint128::from(liquidity),
+            },
+            &[],
+        )
+        .unwrap();
+
+    suite.redeem(USER, 300_000).unwrap();
+    suite.redeem(USER, 200_000).unwrap();
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(queue.depth, 2);
+
+    // the first ticket drains the reserve, the second is paid by the market alone
+    let market = suite.market.clone();
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(BORROWER),
+            market,
+            &coins(400_000, STABLE_DENOM),
+        )
+        .unwrap();
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::ProcessRedemptions {})
+        .unwrap();
+    assert_eq!(suite.stable_balance(USER), 9_500_000);
+    assert_eq!(suite.stable_balance(suite.pool.as_str()), 0);
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(queue.depth, 0);
+    let info: PoolInfoResponse = suite.query(&QueryMsg::PoolInfo {});
+    assert_eq!(info.reserve, Uint256::zero());
+    assert_eq!(info.atoken_balance, Uint256::from(500_000u64));
+}
+
+#[test]
+fn queued_ticket_below_min_receive_is_refunded() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.configure(Some(RedemptionPolicy::ProRata), None, Some(true));
+    suite.deposit(USER, 1_000_000).unwrap();
+
+    let liquidity = suite.stable_balance(suite.market.as_str());
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            suite.market.clone(),
+            &mock_anchor::ExecuteMsg::Lend {
+                borrower: BORROWER.to_string(),
+                amount: Uint128::from(liquidity),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let dp_token = suite.dp_token.clone();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            dp_token.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: suite.pool.to_string(),
+                amount: Uint128::from(500_000u128),
+                msg: to_binary(&Cw20HookMsg::Redeem {
+                    recipient: None,
+                    min_receive: Some(Uint256::from(500_000u64)),
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+    suite.redeem(USER, 200_000).unwrap();
+    let tickets: RedemptionTicketsResponse = suite.query(&QueryMsg::RedemptionTicketsOf {
+        owner: USER.to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(tickets.tickets[0].min_receive, Uint256::from(500_000u64));
+    assert_eq!(tickets.tickets[1].min_receive, Uint256::zero());
+
+    // the market lost 20% while the tickets waited
+    suite.set_exchange_rate(Decimal256::percent(80));
+    let market = suite.market.clone();
+    suite
+        .app
+        .send_tokens(
+            Addr::unchecked(BORROWER),
+            market,
+            &coins(liquidity, STABLE_DENOM),
+        )
+        .unwrap();
+    suite
+        .execute(BENEFICIARY, &ExecuteMsg::ProcessRedemptions {})
+        .unwrap();
+
+    // the first ticket would pay 400_000, so its DP goes back; the second takes its share of the loss
+    assert_eq!(suite.stable_balance(USER), 9_160_000);
+    assert_eq!(suite.token_balance(&dp_token, USER), 800_000);
+    assert_eq!(suite.token_balance(&dp_token, suite.pool.as_str()), 0);
+    let queue: RedemptionQueueResponse = suite.query(&QueryMsg::RedemptionQueue {});
+    assert_eq!(queue.depth, 0);
+    let deposit: DepositAmountResponse = suite.query(&QueryMsg::DepositAmountAt {
+        owner: USER.to_string(),
+        height: suite.app.block_info().height,
+    });
+    assert_eq!(deposit.amount, Uint256::from(800_000u64));
+}
+
+#[test]
+fn redeployed_pool_adopts_dp_token() {
+    let mut suite = Suite::new(Decimal256::one());
+    // DP of a retired pool, its wasm admin kept by the owner for the handover
+    let legacy_token = suite
+        .app
+        .instantiate_contract(
+            suite.token_code_id,
+            Addr::unchecked(OWNER),
+            &cw20_base::msg::InstantiateMsg {
+                name: "Deposit Token - legacy".to_string(),
+                symbol: "PylonDP".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: USER.to_string(),
+                    amount: Uint128::from(1_000_000u128),
+                }],
+                mint: Some(MinterResponse {
+                    minter: "legacy-pool".to_string(),
+                    cap: None,
+                }),
+                marketing: None,
+            },
+            &[],
+            "legacy DP",
+            Some(OWNER.to_string()),
+        )
+        .unwrap();
+
+    let pool = suite
+        .app
+        .instantiate_contract(
+            suite.pool_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                pool_name: "redeployed".to_string(),
+                beneficiary: BENEFICIARY.to_string(),
+                fee_collector: FEE_COLLECTOR.to_string(),
+                fee_rate: Decimal256::percent(20),
+                moneymarket: suite.market.to_string(),
+                moneymarket_kind: MoneyMarketKind::Anchor,
+                dp_code_id: None,
+                dp_token: Some(legacy_token.to_string()),
+                dp_token_name: None,
+                dp_token_symbol: None,
+                dp_token_marketing: None,
+                dp_token_label: None,
+                dp_token_admin: None,
+            },
+            &[],
+            "pool",
+            None,
+        )
+        .unwrap();
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(OWNER), coins(1_000_000, STABLE_DENOM))
+        .unwrap();
+    let adopt = |suite: &mut Suite| {
+        suite.app.execute_contract(
+            Addr::unchecked(OWNER),
+            pool.clone(),
+            &ExecuteMsg::AdoptDpToken {},
+            &coins(1_000_000, STABLE_DENOM),
+        )
+    };
+
+    // the legacy pool still mints the token
+    adopt(&mut suite).unwrap_err();
+    suite
+        .app
+        .migrate_contract(
+            Addr::unchecked(OWNER),
+            legacy_token.clone(),
+            &DpTokenMigrateMsg {
+                minter: Some(pool.to_string()),
+            },
+            suite.dp_code_id,
+        )
+        .unwrap();
+    // holders keep trading while the new pool awaits adoption
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            legacy_token.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: BORROWER.to_string(),
+                amount: Uint128::from(100_000u64),
+            },
+            &[],
+        )
+        .unwrap();
+    adopt(&mut suite).unwrap();
+
+    // the holder is recorded once synced, and redeems the adopted DP from the reserve
+    suite.pool = pool;
+    suite.dp_token = legacy_token.clone();
+    suite
+        .execute(
+            BENEFICIARY,
+            &ExecuteMsg::SyncBalances {
+                owners: vec![USER.to_string()],
+            },
+        )
+        .unwrap();
+    let deposit: DepositAmountResponse = suite.query(&QueryMsg::DepositAmountAt {
+        owner: USER.to_string(),
+        height: suite.app.block_info().height,
+    });
+    assert_eq!(deposit.amount, Uint256::from(900_000u64));
+
+    suite.redeem(USER, 400_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 10_400_000);
+    assert_eq!(suite.token_balance(&legacy_token, USER), 500_000);
+    let info: PoolInfoResponse = suite.query(&QueryMsg::PoolInfo {});
+    assert_eq!(info.reserve, Uint256::from(600_000u64));
+}
+
+#[test]
+fn direct_transfer_keeps_snapshots() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.deposit(USER, 1_000_000).unwrap();
+    let deposited_at = suite.app.block_info().height;
+    suite.app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+
+    // straight to the DP token, the pool only hears of it from the token
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.dp_token.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: BORROWER.to_string(),
+                amount: Uint128::from(400_000u64),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let deposit_at = |suite: &Suite, owner: &str, height: u64| -> Uint256 {
+        let deposit: DepositAmountResponse = suite.query(&QueryMsg::DepositAmountAt {
+            owner: owner.to_string(),
+            height,
+        });
+        deposit.amount
+    };
+    let height = suite.app.block_info().height;
+    assert_eq!(deposit_at(&suite, USER, height), Uint256::from(600_000u64));
+    assert_eq!(
+        deposit_at(&suite, BORROWER, height),
+        Uint256::from(400_000u64)
+    );
+    assert_eq!(
+        deposit_at(&suite, USER, deposited_at),
+        Uint256::from(1_000_000u64)
+    );
+    assert_eq!(deposit_at(&suite, BORROWER, deposited_at), Uint256::zero());
+}
+
+#[test]
+fn direct_transfer_splits_deposit_share() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.deposit(USER, 1_000_000).unwrap();
+    let start = suite.app.block_info().time.seconds();
+    let advance = |suite: &mut Suite| {
+        suite.app.update_block(|block| {
+            block.height += 1;
+            block.time = block.time.plus_seconds(10);
+        })
+    };
+
+    advance(&mut suite);
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(USER),
+            suite.dp_token.clone(),
+            &Cw20ExecuteMsg::Transfer {
+                recipient: BORROWER.to_string(),
+                amount: Uint128::from(400_000u64),
+            },
+            &[],
+        )
+        .unwrap();
+    advance(&mut suite);
+
+    let share_of = |suite: &Suite, owner: &str| -> DepositShareResponse {
+        suite.query(&QueryMsg::DepositShareOf {
+            owner: owner.to_string(),
+            start,
+            end: start + 20,
+        })
+    };
+    // user: 1_000_000 * 10 + 600_000 * 10, borrower: 400_000 * 10
+    let user = share_of(&suite, USER);
+    let borrower = share_of(&suite, BORROWER);
+    assert_eq!(user.share, Decimal256::percent(80));
+    assert_eq!(borrower.share, Decimal256::percent(20));
+    assert_eq!(user.amount + borrower.amount, user.total);
+}
+
+#[test]
+fn default_dp_token_moves_to_redeployed_pool() {
+    let mut suite = Suite::new(Decimal256::one());
+    suite.deposit(USER, 1_000_000).unwrap();
+    let dp_token = suite.dp_token.clone();
+
+    let pool = suite
+        .app
+        .instantiate_contract(
+            suite.pool_code_id,
+            Addr::unchecked(OWNER),
+            &InstantiateMsg {
+                pool_name: "redeployed".to_string(),
+                beneficiary: BENEFICIARY.to_string(),
+                fee_collector: FEE_COLLECTOR.to_string(),
+                fee_rate: Decimal256::percent(20),
+                moneymarket: suite.market.to_string(),
+                moneymarket_kind: MoneyMarketKind::Anchor,
+                dp_code_id: None,
+                dp_token: Some(dp_token.to_string()),
+                dp_token_name: None,
+                dp_token_symbol: None,
+                dp_token_marketing: None,
+                dp_token_label: None,
+                dp_token_admin: None,
+            },
+            &[],
+            "pool",
+            None,
+        )
+        .unwrap();
+
+    // the old pool is the token's wasm admin, its owner hands minting over
+    let handover = ExecuteMsg::MigrateDpToken {
+        code_id: suite.dp_code_id,
+        msg: to_binary(&DpTokenMigrateMsg {
+            minter: Some(pool.to_string()),
+        })
+        .unwrap(),
+    };
+    suite.execute(USER, &handover).unwrap_err();
+    suite.execute(OWNER, &handover).unwrap();
+
+    suite
+        .app
+        .init_bank_balance(&Addr::unchecked(OWNER), coins(1_000_000, STABLE_DENOM))
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked(OWNER),
+            pool.clone(),
+            &ExecuteMsg::AdoptDpToken {},
+            &coins(1_000_000, STABLE_DENOM),
+        )
+        .unwrap();
+
+    // the old pool can no longer mint
+    suite.deposit(USER, 100_000).unwrap_err();
+    suite.pool = pool;
+
+    // adopted holders read as empty until their DP moves or is synced
+    let deposit_at = |suite: &Suite| -> Uint256 {
+        let deposit: DepositAmountResponse = suite.query(&QueryMsg::DepositAmountAt {
+            owner: USER.to_string(),
+            height: suite.app.block_info().height,
+        });
+        deposit.amount
+    };
+    assert_eq!(deposit_at(&suite), Uint256::zero());
+    suite.deposit(USER, 100_000).unwrap();
+    assert_eq!(suite.token_balance(&dp_token, USER), 1_100_000);
+    assert_eq!(deposit_at(&suite), Uint256::from(1_100_000u64));
+
+    suite.redeem(USER, 1_100_000).unwrap();
+    assert_eq!(suite.stable_balance(USER), 10_000_000);
+    assert_eq!(suite.token_balance(&dp_token, USER), 0);
+}
@@ -1,13 +1,26 @@
 pub mod config;
 pub mod contract;
 
+mod capacity;
+mod dp_token;
+mod earn_history;
 mod error;
 mod handler;
+mod migration;
+mod pause;
 mod querier;
-mod response;
+mod redemption_queue;
+mod reward;
+mod settlement;
+mod simulation;
+mod snapshot;
+mod state;
+mod time_weight;
 
 #[cfg(test)]
-mod mock_querier;
+mod mock_anchor;
 
+#[cfg(test)]
+mod integration_test;
 #[cfg(test)]
 mod test;
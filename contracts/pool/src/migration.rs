@@ -0,0 +1,86 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, DepsMut, Env};
+use cosmwasm_storage::{singleton, singleton_read};
+use pylon_core::address;
+use pylon_core::pool_msg::{MoneyMarketKind, RedemptionPolicy};
+use pylon_utils::token;
+
+use crate::config;
+use crate::error::ContractError;
+use crate::redemption_queue;
+use crate::snapshot;
+use crate::state;
+use crate::time_weight;
+
+/// Config layout of pools deployed before contract versioning (0.1.x)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub this: CanonicalAddr,
+    pub owner: CanonicalAddr,
+    pub beneficiary: CanonicalAddr,
+    pub fee_collector: CanonicalAddr,
+    pub moneymarket: CanonicalAddr,
+    pub atoken: CanonicalAddr,
+    pub stable_denom: String,
+    pub dp_token: CanonicalAddr,
+}
+
+/// Fee rate legacy pools were hardcoded with (20%)
+pub fn legacy_fee_rate() -> Decimal256 {
+    Decimal256::percent(20)
+}
+
+/// 0.1.x -> current: the settings added since get their defaults, the reserve, lifetime totals
+/// and redemption queue start empty. the whole DP supply starts accruing time weight now,
+/// each holder's own record starts with their next deposit, redeem, transfer or `sync_balances`
+pub fn from_legacy(deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    let legacy: LegacyConfig = singleton_read(deps.storage, config::CONFIG_KEY).load()?;
+    let dp_token = address::humanize(deps.api, &legacy.dp_token)?;
+    let dp_total_supply = token::total_supply(deps.as_ref(), dp_token.to_string())?;
+
+    config::store(
+        deps.storage,
+        &config::Config {
+            this: legacy.this,
+            owner: legacy.owner,
+            pauser: None,
+            beneficiary: legacy.beneficiary,
+            beneficiaries: vec![],
+            fee_collector: legacy.fee_collector,
+            fee_rate: legacy_fee_rate(),
+            keeper: None,
+            moneymarket: legacy.moneymarket,
+            moneymarket_kind: MoneyMarketKind::Anchor,
+            atoken: legacy.atoken,
+            stable_denom: legacy.stable_denom,
+            dp_token: legacy.dp_token,
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            redemption_policy: RedemptionPolicy::FirstCome,
+            reserve_ratio: Decimal256::zero(),
+            queue_redemptions: false,
+        },
+    )?;
+    // legacy pools kept no state, lifetime totals count from this upgrade
+    state::store(
+        deps.storage,
+        &state::State {
+            last_earned_at: 0,
+            reserve: Uint256::zero(),
+            dust: Uint256::zero(),
+            total_deposited: Uint256::zero(),
+            total_redeemed: Uint256::zero(),
+            total_yield_earned: Uint256::zero(),
+            total_fee: Uint256::zero(),
+        },
+    )?;
+    singleton(deps.storage, redemption_queue::QUEUE_KEY)
+        .save(&redemption_queue::Queue::default())?;
+    time_weight::increase_total(deps.storage, env.block.time.seconds(), dp_total_supply)?;
+    snapshot::store_total_deposit_amount(deps.storage, env.block.height, dp_total_supply)?;
+
+    Ok(())
+}
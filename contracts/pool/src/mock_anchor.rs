@@ -0,0 +1,161 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cosmwasm_storage::{singleton, singleton_read};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use pylon_utils::token;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::querier::anchor::{ConfigResponse, Cw20HookMsg, EpochStateResponse, QueryMsg};
+
+static STATE_KEY: &[u8] = b"state";
+
+/// Anchor market stand-in for multi-contract tests.
+/// Mints aUST at `amount / exchange_rate` and pays back `amount * exchange_rate` on redeem.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub stable_denom: String,
+    pub exchange_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    DepositStable {},
+    /// aUST has to be instantiated with the market as minter, so it is registered afterwards
+    RegisterAterra {
+        aterra_contract: String,
+    },
+    SetExchangeRate {
+        exchange_rate: Decimal256,
+    },
+    /// hands out market liquidity, as borrowers would
+    Lend {
+        borrower: String,
+        amount: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct State {
+    stable_denom: String,
+    exchange_rate: Decimal256,
+    aterra_contract: Option<Addr>,
+}
+
+fn aterra_contract(state: &State) -> StdResult<Addr> {
+    state
+        .aterra_contract
+        .clone()
+        .ok_or_else(|| StdError::generic_err("mock anchor: aterra contract not registered"))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    singleton(deps.storage, STATE_KEY).save(&State {
+        stable_denom: msg.stable_denom,
+        exchange_rate: msg.exchange_rate,
+        aterra_contract: None,
+    })?;
+
+    Ok(Response::default())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    let mut state: State = singleton_read(deps.storage, STATE_KEY).load()?;
+
+    match msg {
+        ExecuteMsg::Receive(cw20_msg) => {
+            if info.sender != aterra_contract(&state)? {
+                return Err(StdError::generic_err("mock anchor: unknown token"));
+            }
+            match from_binary(&cw20_msg.msg)? {
+                Cw20HookMsg::RedeemStable {} => {
+                    let redeemed = Uint256::from(cw20_msg.amount) * state.exchange_rate;
+
+                    Ok(Response::new()
+                        .add_message(WasmMsg::Execute {
+                            contract_addr: info.sender.to_string(),
+                            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                                amount: cw20_msg.amount,
+                            })?,
+                            funds: vec![],
+                        })
+                        .add_message(BankMsg::Send {
+                            to_address: cw20_msg.sender,
+                            amount: coins(Uint128::from(redeemed).u128(), state.stable_denom),
+                        }))
+                }
+            }
+        }
+        ExecuteMsg::DepositStable {} => {
+            let deposited = info
+                .funds
+                .iter()
+                .find(|c| c.denom == state.stable_denom)
+                .map(|c| Uint256::from(c.amount))
+                .unwrap_or_else(Uint256::zero);
+
+            Ok(Response::new().add_message(WasmMsg::Execute {
+                contract_addr: aterra_contract(&state)?.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Mint {
+                    recipient: info.sender.to_string(),
+                    amount: (deposited / state.exchange_rate).into(),
+                })?,
+                funds: vec![],
+            }))
+        }
+        ExecuteMsg::RegisterAterra { aterra_contract } => {
+            state.aterra_contract = Some(deps.api.addr_validate(aterra_contract.as_str())?);
+            singleton(deps.storage, STATE_KEY).save(&state)?;
+
+            Ok(Response::default())
+        }
+        ExecuteMsg::SetExchangeRate { exchange_rate } => {
+            state.exchange_rate = exchange_rate;
+            singleton(deps.storage, STATE_KEY).save(&state)?;
+
+            Ok(Response::default())
+        }
+        ExecuteMsg::Lend { borrower, amount } => Ok(Response::new().add_message(BankMsg::Send {
+            to_address: borrower,
+            amount: coins(amount.u128(), state.stable_denom),
+        })),
+    }
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let state: State = singleton_read(deps.storage, STATE_KEY).load()?;
+    let aterra_contract = aterra_contract(&state)?;
+
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            owner_addr: "".to_string(),
+            aterra_contract: aterra_contract.to_string(),
+            interest_model: "".to_string(),
+            distribution_model: "".to_string(),
+            overseer_contract: "".to_string(),
+            collector_contract: "".to_string(),
+            distributor_contract: "".to_string(),
+            stable_denom: state.stable_denom,
+            max_borrow_factor: Decimal256::one(),
+        }),
+        QueryMsg::EpochState { .. } => to_binary(&EpochStateResponse {
+            exchange_rate: state.exchange_rate,
+            aterra_supply: token::total_supply(deps, aterra_contract.to_string())?,
+        }),
+    }
+}
@@ -0,0 +1,47 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read};
+
+use crate::error::ContractError;
+
+pub static PAUSE_KEY: &[u8] = b"pause";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Pause {
+    pub deposit: bool,
+    pub redeem: bool,
+    pub earn: bool,
+}
+
+pub fn store(storage: &mut dyn Storage, data: &Pause) -> StdResult<()> {
+    singleton(storage, PAUSE_KEY).save(data)
+}
+
+pub fn read(storage: &dyn Storage) -> StdResult<Pause> {
+    Ok(singleton_read(storage, PAUSE_KEY)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn assert_deposit_allowed(storage: &dyn Storage) -> Result<(), ContractError> {
+    if read(storage)?.deposit {
+        return Err(ContractError::DepositPaused {});
+    }
+    Ok(())
+}
+
+pub fn assert_redeem_allowed(storage: &dyn Storage) -> Result<(), ContractError> {
+    if read(storage)?.redeem {
+        return Err(ContractError::RedeemPaused {});
+    }
+    Ok(())
+}
+
+pub fn assert_earn_allowed(storage: &dyn Storage) -> Result<(), ContractError> {
+    if read(storage)?.earn {
+        return Err(ContractError::EarnPaused {});
+    }
+    Ok(())
+}
@@ -5,6 +5,8 @@ use pylon_utils::tax::deduct_tax;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::querier::moneymarket::MoneyMarket;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
@@ -32,7 +34,7 @@ pub struct ConfigResponse {
 pub fn config(deps: Deps, market: &CanonicalAddr) -> StdResult<ConfigResponse> {
     let market_config: ConfigResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: deps.api.addr_humanize(market).unwrap().to_string(),
+            contract_addr: deps.api.addr_humanize(market)?.to_string(),
             msg: to_binary(&QueryMsg::Config {})?,
         }))?;
 
@@ -49,7 +51,7 @@ pub struct EpochStateResponse {
 pub fn epoch_state(deps: Deps, market: &CanonicalAddr) -> StdResult<EpochStateResponse> {
     let epoch_state: EpochStateResponse =
         deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-            contract_addr: deps.api.addr_humanize(market).unwrap().to_string(),
+            contract_addr: deps.api.addr_humanize(market)?.to_string(),
             msg: to_binary(&QueryMsg::EpochState {
                 block_height: None,
                 distributed_interest: None,
@@ -80,7 +82,7 @@ pub fn deposit_stable_msg(
     amount: Uint128,
 ) -> StdResult<Vec<CosmosMsg>> {
     Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps.api.addr_humanize(market).unwrap().to_string(),
+        contract_addr: deps.api.addr_humanize(market)?.to_string(),
         msg: to_binary(&HandleMsg::DepositStable {})?,
         funds: vec![deduct_tax(
             deps,
@@ -99,12 +101,49 @@ pub fn redeem_stable_msg(
     amount: Uint128,
 ) -> StdResult<Vec<CosmosMsg>> {
     Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: deps.api.addr_humanize(token).unwrap().to_string(),
+        contract_addr: deps.api.addr_humanize(token)?.to_string(),
         msg: to_binary(&Cw20ExecuteMsg::Send {
-            contract: deps.api.addr_humanize(market).unwrap().to_string(),
+            contract: deps.api.addr_humanize(market)?.to_string(),
             amount,
-            msg: to_binary(&Cw20HookMsg::RedeemStable {}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::RedeemStable {})?,
         })?,
         funds: vec![],
     })])
 }
+
+pub struct Anchor {
+    pub market: CanonicalAddr,
+    pub atoken: CanonicalAddr,
+    pub stable_denom: String,
+}
+
+impl MoneyMarket for Anchor {
+    fn stable_denom(&self) -> String {
+        self.stable_denom.clone()
+    }
+
+    fn share_token(&self) -> CanonicalAddr {
+        self.atoken.clone()
+    }
+
+    fn exchange_rate(&self, deps: Deps) -> StdResult<Decimal256> {
+        Ok(epoch_state(deps, &self.market)?.exchange_rate)
+    }
+
+    fn liquidity(&self, deps: Deps) -> StdResult<Uint256> {
+        let balance = deps.querier.query_balance(
+            deps.api.addr_humanize(&self.market)?,
+            self.stable_denom.as_str(),
+        )?;
+
+        Ok(balance.amount.into())
+    }
+
+    fn deposit_msgs(&self, deps: Deps, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+        deposit_stable_msg(deps, &self.market, &self.stable_denom, amount)
+    }
+
+    fn redeem_msgs(&self, deps: Deps, share_amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+        redeem_stable_msg(deps, &self.market, &self.atoken, share_amount)
+    }
+}
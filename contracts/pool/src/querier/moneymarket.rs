@@ -0,0 +1,63 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, CosmosMsg, Deps, StdResult, Uint128};
+use pylon_core::pool_msg::MoneyMarketKind;
+
+use crate::config;
+use crate::querier::anchor;
+
+/// Compound-style money market the pool deposits its stable coins into.
+/// Deposits are exchanged for a share token (e.g. aUST) which accrues value over time.
+pub trait MoneyMarket {
+    /// stable coin denom accepted by the market
+    fn stable_denom(&self) -> String;
+
+    /// token received in exchange of deposited stable coins
+    fn share_token(&self) -> CanonicalAddr;
+
+    /// stable coin value of a single share token
+    fn exchange_rate(&self, deps: Deps) -> StdResult<Decimal256>;
+
+    /// stable coins the market can pay out right now
+    fn liquidity(&self, deps: Deps) -> StdResult<Uint256>;
+
+    /// messages depositing `amount` of stable coins held by the pool
+    fn deposit_msgs(&self, deps: Deps, amount: Uint128) -> StdResult<Vec<CosmosMsg>>;
+
+    /// messages exchanging `share_amount` of share tokens back to stable coins
+    fn redeem_msgs(&self, deps: Deps, share_amount: Uint128) -> StdResult<Vec<CosmosMsg>>;
+}
+
+pub struct MarketInfo {
+    pub stable_denom: String,
+    pub share_token: CanonicalAddr,
+}
+
+/// resolves stable denom and share token of a market while instantiating the pool
+pub fn describe(
+    deps: Deps,
+    kind: &MoneyMarketKind,
+    market: &CanonicalAddr,
+) -> StdResult<MarketInfo> {
+    match kind {
+        MoneyMarketKind::Anchor => {
+            let market_config = anchor::config(deps, market)?;
+
+            Ok(MarketInfo {
+                stable_denom: market_config.stable_denom,
+                share_token: deps
+                    .api
+                    .addr_canonicalize(market_config.aterra_contract.as_str())?,
+            })
+        }
+    }
+}
+
+pub fn load(config: &config::Config) -> Box<dyn MoneyMarket> {
+    match config.moneymarket_kind {
+        MoneyMarketKind::Anchor => Box::new(anchor::Anchor {
+            market: config.moneymarket.clone(),
+            atoken: config.atoken.clone(),
+            stable_denom: config.stable_denom.clone(),
+        }),
+    }
+}
@@ -0,0 +1,125 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_storage::{bucket, bucket_read, singleton, singleton_read, Bucket, ReadonlyBucket};
+use pylon_utils::range::calc_range_start;
+
+pub static PREFIX_TICKET: &[u8] = b"redemption_ticket";
+pub static PREFIX_OWNER_TICKET: &[u8] = b"redemption_ticket_owner";
+pub static QUEUE_KEY: &[u8] = b"redemption_queue";
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// DP locked in the pool until the money market can pay for it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ticket {
+    pub id: u64,
+    pub owner: CanonicalAddr,
+    pub recipient: CanonicalAddr,
+    pub amount: Uint256,
+    /// least stable coins the recipient accepts, or the DP is returned to `owner`
+    pub min_receive: Uint256,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Queue {
+    pub last_id: u64,
+    pub depth: u64,
+    pub pending_amount: Uint256,
+}
+
+pub fn read(storage: &dyn Storage) -> StdResult<Queue> {
+    Ok(singleton_read(storage, QUEUE_KEY)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+fn owner_namespace(owner: &CanonicalAddr) -> [&[u8]; 2] {
+    [PREFIX_OWNER_TICKET, owner.as_slice()]
+}
+
+/// appends a ticket to the end of the queue
+pub fn push(
+    storage: &mut dyn Storage,
+    owner: CanonicalAddr,
+    recipient: CanonicalAddr,
+    amount: Uint256,
+    min_receive: Uint256,
+    created_at: u64,
+) -> StdResult<Ticket> {
+    let mut queue = read(storage)?;
+    queue.last_id += 1;
+    queue.depth += 1;
+    queue.pending_amount += amount;
+    singleton(storage, QUEUE_KEY).save(&queue)?;
+
+    let ticket = Ticket {
+        id: queue.last_id,
+        owner,
+        recipient,
+        amount,
+        min_receive,
+        created_at,
+    };
+    bucket(storage, PREFIX_TICKET).save(&ticket.id.to_be_bytes(), &ticket)?;
+    Bucket::multilevel(storage, &owner_namespace(&ticket.owner))
+        .save(&ticket.id.to_be_bytes(), &ticket.id)?;
+
+    Ok(ticket)
+}
+
+/// removes a filled or refunded ticket
+pub fn remove(storage: &mut dyn Storage, ticket: &Ticket) -> StdResult<()> {
+    let mut queue = read(storage)?;
+    queue.depth -= 1;
+    queue.pending_amount = queue.pending_amount - ticket.amount;
+    singleton(storage, QUEUE_KEY).save(&queue)?;
+
+    bucket::<Ticket>(storage, PREFIX_TICKET).remove(&ticket.id.to_be_bytes());
+    Bucket::<u64>::multilevel(storage, &owner_namespace(&ticket.owner))
+        .remove(&ticket.id.to_be_bytes());
+
+    Ok(())
+}
+
+/// ticket filled next, if any
+pub fn first(storage: &dyn Storage) -> StdResult<Option<Ticket>> {
+    Ok(bucket_read(storage, PREFIX_TICKET)
+        .range(None, None, Order::Ascending)
+        .next()
+        .transpose()?
+        .map(|(_, ticket)| ticket))
+}
+
+/// oldest tickets in the order they have to be filled, at most `MAX_LIMIT` of them
+pub fn front(storage: &dyn Storage) -> StdResult<Vec<Ticket>> {
+    bucket_read(storage, PREFIX_TICKET)
+        .range(None, None, Order::Ascending)
+        .take(MAX_LIMIT as usize)
+        .map(|item| item.map(|(_, ticket)| ticket))
+        .collect()
+}
+
+/// pending tickets of `owner` in ascending order of id
+pub fn tickets_of(
+    storage: &dyn Storage,
+    owner: &CanonicalAddr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Ticket>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    ReadonlyBucket::<u64>::multilevel(storage, &owner_namespace(owner))
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, id) = item?;
+            bucket_read(storage, PREFIX_TICKET).load(&id.to_be_bytes())
+        })
+        .collect()
+}
@@ -0,0 +1,156 @@
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, Coin, Deps, Env, StdResult};
+use pylon_utils::tax::deduct_tax;
+use pylon_utils::token;
+use std::ops::{Mul, Sub};
+
+use crate::config;
+use crate::querier::moneymarket;
+use crate::state;
+
+pub struct Reward {
+    pub exchange_rate: Decimal256,
+    pub deficit: Uint256,
+    pub earnable: Uint256,
+    pub fee: Uint256,
+    pub bounty: Uint256,
+}
+
+impl Reward {
+    /// earnable amount left for the beneficiary after the protocol fee and keeper bounty
+    pub fn amount(&self) -> Uint256 {
+        self.earnable.sub(self.fee).sub(self.bounty)
+    }
+}
+
+/// splits `amount` by weight, rounding dust is added to `config.beneficiary`
+pub fn distribute(config: &config::Config, amount: Uint256) -> Vec<(CanonicalAddr, Uint256)> {
+    let total_weight: u64 = config.beneficiaries.iter().map(|b| b.weight).sum();
+    let mut shares: Vec<(CanonicalAddr, Uint256)> = config
+        .beneficiaries
+        .iter()
+        .map(|b| {
+            (
+                b.address.clone(),
+                amount.multiply_ratio(b.weight, total_weight),
+            )
+        })
+        .collect();
+
+    let distributed = shares
+        .iter()
+        .fold(Uint256::zero(), |acc, (_, share)| acc + *share);
+    let dust = amount.sub(distributed);
+    match shares
+        .iter_mut()
+        .find(|(address, _)| *address == config.beneficiary)
+    {
+        Some((_, share)) => *share += dust,
+        None => shares.push((config.beneficiary.clone(), dust)),
+    }
+
+    shares
+}
+
+pub fn fee(earnable: Uint256, fee_rate: Decimal256) -> Uint256 {
+    earnable.mul(fee_rate)
+}
+
+/// Pool holdings valued at the current exchange rate, the reserve counted at face value
+pub struct Assets {
+    pub exchange_rate: Decimal256,
+    pub atoken_balance: Uint256,
+    pub reserve: Uint256,
+    pub pool_value_locked: Uint256,
+    pub dp_total_supply: Uint256,
+}
+
+impl Assets {
+    /// DP not backed by the pool value, zero while the pool is solvent
+    pub fn deficit(&self) -> Uint256 {
+        if self.pool_value_locked < self.dp_total_supply {
+            self.dp_total_supply.sub(self.pool_value_locked)
+        } else {
+            Uint256::zero()
+        }
+    }
+
+    /// pool value exceeding DP supply, zero while the pool is in deficit
+    pub fn surplus(&self) -> Uint256 {
+        if self.pool_value_locked > self.dp_total_supply {
+            self.pool_value_locked.sub(self.dp_total_supply)
+        } else {
+            Uint256::zero()
+        }
+    }
+}
+
+pub fn assets(deps: Deps, env: &Env, config: &config::Config) -> StdResult<Assets> {
+    let market = moneymarket::load(config);
+
+    let exchange_rate = market.exchange_rate(deps)?;
+    let atoken_balance = token::balance_of(
+        deps,
+        deps.api.addr_humanize(&market.share_token())?.to_string(),
+        env.contract.address.to_string(),
+    )?;
+    let dp_total_supply =
+        token::total_supply(deps, deps.api.addr_humanize(&config.dp_token)?.to_string())?;
+
+    let reserve = state::read(deps.storage)?.reserve;
+    let pool_value_locked = reserve
+        + Uint256::from(
+            deduct_tax(
+                deps,
+                Coin {
+                    denom: market.stable_denom(),
+                    amount: (atoken_balance.mul(exchange_rate)).into(),
+                },
+            )?
+            .amount,
+        );
+
+    Ok(Assets {
+        exchange_rate,
+        atoken_balance,
+        reserve,
+        pool_value_locked,
+        dp_total_supply,
+    })
+}
+
+/// `beneficiary` or any of the weighted `beneficiaries`
+pub fn is_beneficiary(config: &config::Config, caller: &CanonicalAddr) -> bool {
+    *caller == config.beneficiary || config.beneficiaries.iter().any(|b| b.address == *caller)
+}
+
+/// the owner and the beneficiaries earn for themselves, any other caller
+/// goes through keeper mode and takes the bounty
+pub fn pays_bounty(config: &config::Config, caller: &CanonicalAddr) -> bool {
+    *caller != config.owner && !is_beneficiary(config, caller)
+}
+
+// calculate deduct(total_aust_amount * exchange_rate) - (total_dp_balance)
+// nothing is earnable while the pool is in deficit, the bounty is only carved out when `with_bounty`
+pub fn claimable(
+    deps: Deps,
+    env: &Env,
+    config: &config::Config,
+    with_bounty: bool,
+) -> StdResult<Reward> {
+    let assets = assets(deps, env, config)?;
+    let earnable = assets.surplus();
+
+    Ok(Reward {
+        exchange_rate: assets.exchange_rate,
+        deficit: assets.deficit(),
+        earnable,
+        fee: fee(earnable, config.fee_rate),
+        bounty: config
+            .keeper
+            .as_ref()
+            .filter(|_| with_bounty)
+            .map(|keeper| earnable.mul(keeper.bounty_rate))
+            .unwrap_or_else(Uint256::zero),
+    })
+}
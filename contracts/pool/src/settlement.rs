@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::{CanonicalAddr, Deps, StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read, Singleton};
+use std::cmp::min;
+use std::ops::Mul;
+use terra_cosmwasm::TerraQuerier;
+
+pub static SETTLEMENT_KEY: &[u8] = b"settlement";
+pub static REFILL_KEY: &[u8] = b"refill";
+pub static CLAIM_KEY: &[u8] = b"claim";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payout {
+    pub recipient: CanonicalAddr,
+    /// least stable coins the recipient accepts, or the redemption is reverted
+    pub min_receive: Uint256,
+    /// stable coins served from the reserve
+    pub reserve_amount: Uint256,
+    /// DP value withdrawn from the money market, weighs the split of what the market returns
+    pub market_amount: Uint256,
+}
+
+/// Redemptions waiting for the money market to return their stable coins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Settlement {
+    /// stable coins held by the pool before the money market paid out
+    pub balance_before: Uint256,
+    pub payouts: Vec<Payout>,
+}
+
+pub fn store(storage: &mut dyn Storage, data: &Settlement) -> StdResult<()> {
+    singleton(storage, SETTLEMENT_KEY).save(data)
+}
+
+pub fn read(storage: &dyn Storage) -> StdResult<Option<Settlement>> {
+    singleton_read(storage, SETTLEMENT_KEY).may_load()
+}
+
+pub fn remove(storage: &mut dyn Storage) {
+    let mut settlement: Singleton<Settlement> = singleton(storage, SETTLEMENT_KEY);
+    settlement.remove()
+}
+
+/// Reserve refill waiting for the money market to return its stable coins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Refill {
+    /// stable coins held by the pool before the money market paid out
+    pub balance_before: Uint256,
+}
+
+pub fn store_refill(storage: &mut dyn Storage, data: &Refill) -> StdResult<()> {
+    singleton(storage, REFILL_KEY).save(data)
+}
+
+pub fn read_refill(storage: &dyn Storage) -> StdResult<Option<Refill>> {
+    singleton_read(storage, REFILL_KEY).may_load()
+}
+
+pub fn remove_refill(storage: &mut dyn Storage) {
+    let mut refill: Singleton<Refill> = singleton(storage, REFILL_KEY);
+    refill.remove()
+}
+
+/// Earned yield waiting for the money market to return its stable coins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Claim {
+    /// stable coins held by the pool before the money market paid out
+    pub balance_before: Uint256,
+    pub caller: CanonicalAddr,
+    pub exchange_rate: Decimal256,
+    /// yield withdrawn from the money market, split into the beneficiaries' amount, `fee` and `bounty`
+    pub earnable: Uint256,
+    pub fee: Uint256,
+    pub bounty: Uint256,
+}
+
+pub fn store_claim(storage: &mut dyn Storage, data: &Claim) -> StdResult<()> {
+    singleton(storage, CLAIM_KEY).save(data)
+}
+
+pub fn read_claim(storage: &dyn Storage) -> StdResult<Option<Claim>> {
+    singleton_read(storage, CLAIM_KEY).may_load()
+}
+
+pub fn remove_claim(storage: &mut dyn Storage) {
+    let mut claim: Singleton<Claim> = singleton(storage, CLAIM_KEY);
+    claim.remove()
+}
+
+/// tax the chain charges on top of sending `amount`
+pub fn transfer_tax(deps: Deps, denom: &str, amount: Uint256) -> StdResult<Uint256> {
+    let terra_querier = TerraQuerier::new(&deps.querier);
+    let tax_rate = Decimal256::from(terra_querier.query_tax_rate()?.rate);
+    let tax_cap = Uint256::from(terra_querier.query_tax_cap(denom.to_string())?.cap);
+
+    Ok(min(amount.mul(tax_rate), tax_cap))
+}
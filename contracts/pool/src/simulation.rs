@@ -0,0 +1,204 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{Coin, Deps, Env, StdResult};
+use pylon_core::pool_msg::RedemptionPolicy;
+use pylon_utils::tax::deduct_tax;
+use pylon_utils::token;
+use std::cmp::min;
+use std::ops::{Div, Mul, Sub};
+
+use crate::config;
+use crate::error::ContractError;
+use crate::querier::moneymarket;
+use crate::reward;
+use crate::state;
+
+pub struct Deposit {
+    /// DP minted, equal to the stable coins that would reach the money market if all were forwarded
+    pub dp_mint_amount: Uint256,
+    /// tax paid forwarding the deposit to the money market
+    pub tax: Uint256,
+    /// stable coins kept in the pool to refill the reserve
+    pub reserve_amount: Uint256,
+    /// share tokens minted by the money market
+    pub atoken_amount: Uint256,
+}
+
+pub struct Redeem {
+    /// stable coins sent to the recipient
+    pub stable_amount: Uint256,
+    /// tax paid withdrawing from the money market and sending to the recipient
+    pub tax: Uint256,
+    /// DP value lost to the deficit under the pro-rata redemption policy
+    pub haircut: Uint256,
+    /// DP value served from the reserve
+    pub reserve_amount: Uint256,
+    /// DP value withdrawn from the money market
+    pub market_amount: Uint256,
+    /// share tokens returned to the money market
+    pub atoken_amount: Uint256,
+}
+
+/// Pool holdings redemptions are paid from
+pub struct Holdings {
+    pub reserve: Uint256,
+    /// only valued under the pro-rata redemption policy, which shares a deficit
+    pub pool_value_locked: Option<Uint256>,
+    pub dp_total_supply: Uint256,
+}
+
+impl Holdings {
+    /// holdings left once `amount` of DP is redeemed as simulated by `redeem`
+    pub fn after(&self, amount: Uint256, redeem: &Redeem) -> Holdings {
+        let paid = redeem.reserve_amount + redeem.market_amount;
+        Holdings {
+            reserve: self.reserve.sub(redeem.reserve_amount),
+            pool_value_locked: self
+                .pool_value_locked
+                .map(|value| value.sub(min(paid, value))),
+            dp_total_supply: self.dp_total_supply.sub(min(amount, self.dp_total_supply)),
+        }
+    }
+}
+
+pub struct Rebalance {
+    /// reserve exceeding the target, deposited into the money market
+    pub deposit_amount: Uint256,
+    /// share tokens redeemed to refill the reserve up to the target
+    pub atoken_amount: Uint256,
+}
+
+fn deduct(deps: Deps, config: &config::Config, amount: Uint256) -> StdResult<Uint256> {
+    Ok(deduct_tax(
+        deps,
+        Coin {
+            denom: config.stable_denom.clone(),
+            amount: amount.into(),
+        },
+    )?
+    .amount
+    .into())
+}
+
+/// reserve the pool aims to hold once `minting` more DP is in circulation
+fn reserve_target(
+    deps: Deps,
+    config: &config::Config,
+    minting: Uint256,
+) -> Result<Uint256, ContractError> {
+    let dp_total_supply = token::total_supply(deps, config.dp_token_addr(deps.api)?.to_string())?;
+
+    Ok((dp_total_supply + minting).mul(config.reserve_ratio))
+}
+
+/// depositing `amount` of stable coins
+pub fn deposit(
+    deps: Deps,
+    config: &config::Config,
+    amount: Uint256,
+) -> Result<Deposit, ContractError> {
+    let exchange_rate = moneymarket::load(config).exchange_rate(deps)?;
+    let dp_mint_amount = deduct(deps, config, amount)?;
+
+    let reserve = state::read(deps.storage)?.reserve;
+    let target = reserve_target(deps, config, dp_mint_amount)?;
+    let reserve_amount = if reserve < target {
+        min(amount, target.sub(reserve))
+    } else {
+        Uint256::zero()
+    };
+    let market_amount = amount.sub(reserve_amount);
+    let forwarded = deduct(deps, config, market_amount)?;
+
+    Ok(Deposit {
+        dp_mint_amount,
+        tax: market_amount.sub(forwarded),
+        reserve_amount,
+        atoken_amount: forwarded.div(exchange_rate),
+    })
+}
+
+pub fn holdings(deps: Deps, env: &Env, config: &config::Config) -> Result<Holdings, ContractError> {
+    Ok(match config.redemption_policy {
+        RedemptionPolicy::FirstCome => Holdings {
+            reserve: state::read(deps.storage)?.reserve,
+            pool_value_locked: None,
+            dp_total_supply: token::total_supply(
+                deps,
+                config.dp_token_addr(deps.api)?.to_string(),
+            )?,
+        },
+        RedemptionPolicy::ProRata => {
+            let assets = reward::assets(deps, env, config)?;
+            Holdings {
+                reserve: assets.reserve,
+                pool_value_locked: Some(assets.pool_value_locked),
+                dp_total_supply: assets.dp_total_supply,
+            }
+        }
+    })
+}
+
+/// redeeming `amount` of DP
+pub fn redeem(
+    deps: Deps,
+    env: &Env,
+    config: &config::Config,
+    amount: Uint256,
+) -> Result<Redeem, ContractError> {
+    redeem_from(deps, config, &holdings(deps, env, config)?, amount)
+}
+
+/// redeeming `amount` of DP out of `holdings`, which may differ from the stored ones
+/// while several redemptions are paid at once
+pub fn redeem_from(
+    deps: Deps,
+    config: &config::Config,
+    holdings: &Holdings,
+    amount: Uint256,
+) -> Result<Redeem, ContractError> {
+    let exchange_rate = moneymarket::load(config).exchange_rate(deps)?;
+    let covered = match (&config.redemption_policy, holdings.pool_value_locked) {
+        (RedemptionPolicy::ProRata, Some(value)) if value < holdings.dp_total_supply => {
+            amount.multiply_ratio(value, holdings.dp_total_supply)
+        }
+        _ => amount,
+    };
+
+    // the reserve is already liquid, only the transfer to the recipient is taxed
+    let reserve_amount = min(covered, holdings.reserve);
+    let market_amount = covered.sub(reserve_amount);
+    let stable_amount = deduct(
+        deps,
+        config,
+        reserve_amount + deduct(deps, config, market_amount)?,
+    )?;
+
+    Ok(Redeem {
+        stable_amount,
+        tax: covered.sub(stable_amount),
+        haircut: amount.sub(covered),
+        reserve_amount,
+        market_amount,
+        atoken_amount: market_amount.div(exchange_rate),
+    })
+}
+
+/// moving the reserve back to its target against the money market
+pub fn rebalance(deps: Deps, config: &config::Config) -> Result<Rebalance, ContractError> {
+    let reserve = state::read(deps.storage)?.reserve;
+    let target = reserve_target(deps, config, Uint256::zero())?;
+
+    if reserve > target {
+        return Ok(Rebalance {
+            deposit_amount: reserve.sub(target),
+            atoken_amount: Uint256::zero(),
+        });
+    }
+
+    let exchange_rate = moneymarket::load(config).exchange_rate(deps)?;
+
+    Ok(Rebalance {
+        deposit_amount: Uint256::zero(),
+        atoken_amount: target.sub(reserve).div(exchange_rate),
+    })
+}
@@ -0,0 +1,65 @@
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_storage::{Bucket, ReadonlyBucket};
+
+pub static PREFIX_SNAPSHOT: &[u8] = b"snapshot";
+pub static PREFIX_TOTAL_SNAPSHOT: &[u8] = b"total_snapshot";
+
+fn user_namespace(owner: &CanonicalAddr) -> [&[u8]; 2] {
+    [PREFIX_SNAPSHOT, owner.as_slice()]
+}
+
+fn store(
+    storage: &mut dyn Storage,
+    namespaces: &[&[u8]],
+    height: u64,
+    amount: Uint256,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, namespaces).save(&height.to_be_bytes(), &amount)
+}
+
+fn read_at(storage: &dyn Storage, namespaces: &[&[u8]], height: u64) -> StdResult<Uint256> {
+    let latest = ReadonlyBucket::<Uint256>::multilevel(storage, namespaces)
+        .range(
+            None,
+            Some(&height.saturating_add(1).to_be_bytes()),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+
+    Ok(latest
+        .map(|(_, amount)| amount)
+        .unwrap_or_else(Uint256::zero))
+}
+
+/// records DP balance of `owner` at the end of block `height`
+pub fn store_deposit_amount(
+    storage: &mut dyn Storage,
+    owner: &CanonicalAddr,
+    height: u64,
+    amount: Uint256,
+) -> StdResult<()> {
+    store(storage, &user_namespace(owner), height, amount)
+}
+
+/// records DP supply at the end of block `height`
+pub fn store_total_deposit_amount(
+    storage: &mut dyn Storage,
+    height: u64,
+    amount: Uint256,
+) -> StdResult<()> {
+    store(storage, &[PREFIX_TOTAL_SNAPSHOT], height, amount)
+}
+
+pub fn deposit_amount_at(
+    storage: &dyn Storage,
+    owner: &CanonicalAddr,
+    height: u64,
+) -> StdResult<Uint256> {
+    read_at(storage, &user_namespace(owner), height)
+}
+
+pub fn total_deposit_amount_at(storage: &dyn Storage, height: u64) -> StdResult<Uint256> {
+    read_at(storage, &[PREFIX_TOTAL_SNAPSHOT], height)
+}
@@ -0,0 +1,32 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read};
+
+pub static STATE_KEY: &[u8] = b"state";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct State {
+    pub last_earned_at: u64,
+    // UST kept liquid in the pool for redemptions
+    pub reserve: Uint256,
+    // UST left over by redemption payouts, owned by nobody
+    pub dust: Uint256,
+    // lifetime totals, in UST
+    pub total_deposited: Uint256,
+    pub total_redeemed: Uint256,
+    pub total_yield_earned: Uint256,
+    pub total_fee: Uint256,
+}
+
+pub fn store(storage: &mut dyn Storage, data: &State) -> StdResult<()> {
+    singleton(storage, STATE_KEY).save(data)
+}
+
+pub fn read(storage: &dyn Storage) -> StdResult<State> {
+    Ok(singleton_read(storage, STATE_KEY)
+        .may_load()?
+        .unwrap_or_default())
+}
@@ -1,26 +1,50 @@
+use crate::config;
 use crate::contract;
-use crate::mock_querier::mock_dependencies;
+use crate::dp_token;
+use crate::error::ContractError;
+use crate::handler::core as CoreHandler;
+use crate::migration::LegacyConfig;
 use crate::querier::anchor::{
-    ConfigResponse, EpochStateResponse, QueryMsg as AnchorQueryMsg, QueryMsg,
+    ConfigResponse, Cw20HookMsg as AnchorHookMsg, EpochStateResponse, HandleMsg as AnchorHandleMsg,
+    QueryMsg as AnchorQueryMsg, QueryMsg,
 };
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, to_binary};
-use pylon_core::pool_msg::InstantiateMsg;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, Api, BankMsg, Binary, ContractResult, CosmosMsg, Decimal,
+    Env, OwnedDeps, Reply, ReplyOn, Response, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+use cosmwasm_storage::singleton;
+use cw20::{
+    BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Logo, MinterResponse,
+    TokenInfoResponse,
+};
+use pylon_core::pool_msg::{
+    Beneficiary, Cw20HookMsg, DpTokenMarketing, ExecuteMsg, InstantiateMsg, Keeper, MigrateMsg,
+    MoneyMarketKind, QueryMsg as PoolQueryMsg, RedemptionPolicy,
+};
+use pylon_core::pool_resp::{
+    BeneficiaryReward, ClaimableRewardResponse, ConfigResponse as PoolConfigResponse,
+    DepositAmountResponse, DepositBalanceSecondsResponse, DepositCapacityResponse,
+    DepositShareResponse, EarnHistoryResponse, HarvestResponse, PauseInfoResponse,
+    SimulateDepositResponse, SimulateRedeemResponse, SolvencyResponse, TotalDepositAmountResponse,
+};
+use pylon_testing::mock_querier::{mock_dependencies, CustomMockWasmQuerier, MockTax};
+use std::str::FromStr;
 
 const MONEY_MARKET: &str = "money-market";
+const ATOKEN: &str = "token-aust";
+const DP_TOKEN: &str = "token-dp";
 
-#[test]
-fn instantiate() {
-    let mut deps = mock_dependencies(&[]);
-    let env = mock_env();
-    let info = mock_info("owner", &[]);
+type MockDeps = OwnedDeps<MockStorage, MockApi, CustomMockWasmQuerier>;
 
+fn mock_money_market(deps: &mut MockDeps, exchange_rate: Decimal256) {
     deps.querier.register_wasm_smart_query_handler(
         MONEY_MARKET.to_string(),
-        Box::new(|x| match from_binary::<AnchorQueryMsg>(x).unwrap() {
+        Box::new(move |x| match from_binary::<AnchorQueryMsg>(x).unwrap() {
             QueryMsg::Config {} => to_binary(&ConfigResponse {
                 owner_addr: "".to_string(),
-                aterra_contract: "token-aust".to_string(),
+                aterra_contract: ATOKEN.to_string(),
                 interest_model: "".to_string(),
                 distribution_model: "".to_string(),
                 overseer_contract: "".to_string(),
@@ -30,20 +54,2323 @@ fn instantiate() {
                 max_borrow_factor: Default::default(),
             }),
             QueryMsg::EpochState { .. } => to_binary(&EpochStateResponse {
-                exchange_rate: Default::default(),
+                exchange_rate,
                 aterra_supply: Default::default(),
             }),
         }),
     );
+}
 
-    let msg = InstantiateMsg {
+fn mock_token(deps: &mut MockDeps, token: &str, balance: u128, total_supply: u128) {
+    deps.querier.register_wasm_smart_query_handler(
+        token.to_string(),
+        Box::new(move |x| match from_binary::<Cw20QueryMsg>(x).unwrap() {
+            Cw20QueryMsg::Balance { .. } => to_binary(&BalanceResponse {
+                balance: Uint128::from(balance),
+            }),
+            Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                name: "".to_string(),
+                symbol: "".to_string(),
+                decimals: 6,
+                total_supply: Uint128::from(total_supply),
+            }),
+            _ => panic!("testing: unsupported token query"),
+        }),
+    );
+}
+
+/// like `mock_token`, with a balance per address. unlisted addresses hold nothing
+fn mock_token_balances(
+    deps: &mut MockDeps,
+    token: &str,
+    balances: &[(&str, u128)],
+    total_supply: u128,
+) {
+    let balances: Vec<(String, u128)> = balances
+        .iter()
+        .map(|(address, balance)| (address.to_string(), *balance))
+        .collect();
+    deps.querier.register_wasm_smart_query_handler(
+        token.to_string(),
+        Box::new(move |x| match from_binary::<Cw20QueryMsg>(x).unwrap() {
+            Cw20QueryMsg::Balance { address } => to_binary(&BalanceResponse {
+                balance: Uint128::from(
+                    balances
+                        .iter()
+                        .find(|(holder, _)| *holder == address)
+                        .map(|(_, balance)| *balance)
+                        .unwrap_or_default(),
+                ),
+            }),
+            Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                name: "".to_string(),
+                symbol: "".to_string(),
+                decimals: 6,
+                total_supply: Uint128::from(total_supply),
+            }),
+            _ => panic!("testing: unsupported token query"),
+        }),
+    );
+}
+
+/// the money market paid `received` uusd to the pool, settles the pending redemptions
+fn settle(deps: &mut MockDeps, received: u128) -> Result<Response, ContractError> {
+    deps.querier
+        .with_balance(MOCK_CONTRACT_ADDR, &coins(received, "uusd"));
+
+    contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: contract::REDEEM_REPLY_ID,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+}
+
+/// the money market paid `received` uusd for the earned yield, distributes the rewards
+fn distribute(deps: &mut MockDeps, env: Env, received: u128) -> Result<Response, ContractError> {
+    let balance = deps
+        .as_ref()
+        .querier
+        .query_balance(MOCK_CONTRACT_ADDR, "uusd")
+        .unwrap();
+    deps.querier.with_balance(
+        MOCK_CONTRACT_ADDR,
+        &coins(balance.amount.u128() + received, "uusd"),
+    );
+
+    contract::reply(
+        deps.as_mut(),
+        env,
+        Reply {
+            id: contract::EARN_REPLY_ID,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
         pool_name: "test-pool".to_string(),
         beneficiary: "test-beneficiary".to_string(),
         fee_collector: "test-fee-collector".to_string(),
+        fee_rate: Decimal256::percent(20),
         moneymarket: MONEY_MARKET.to_string(),
-        dp_code_id: 666,
-    };
-    let resp = contract::instantiate(deps.as_mut(), env, info, msg)
+        moneymarket_kind: MoneyMarketKind::Anchor,
+        dp_code_id: Some(666),
+        dp_token: None,
+        dp_token_name: None,
+        dp_token_symbol: None,
+        dp_token_marketing: None,
+        dp_token_label: None,
+        dp_token_admin: None,
+    }
+}
+
+fn setup(exchange_rate: Decimal256) -> MockDeps {
+    let mut deps = mock_dependencies(&[]);
+    mock_money_market(&mut deps, exchange_rate);
+
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        instantiate_msg(),
+    )
+    .expect("testing: should init contract");
+    CoreHandler::register_dp_token(deps.as_mut(), mock_env(), Addr::unchecked(DP_TOKEN))
+        .expect("testing: should register dp token");
+
+    deps
+}
+
+#[test]
+fn instantiate() {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+    let info = mock_info("owner", &[]);
+    mock_money_market(&mut deps, Decimal256::one());
+
+    let resp = contract::instantiate(deps.as_mut(), env, info, instantiate_msg())
         .expect("testing: should init contract");
-    println!("{:?}", resp);
+    // the DP token is created through a submessage and registered on its reply
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(resp.messages[0].id, 1);
+    assert_eq!(resp.messages[0].reply_on, ReplyOn::Success);
+
+    // the config query needs the DP token, so check what was stored
+    let config = config::read(deps.as_ref().storage).unwrap();
+    let canonical = |addr: &str| deps.api.addr_canonicalize(addr).unwrap();
+    assert_eq!(config.owner, canonical("owner"));
+    assert_eq!(config.beneficiary, canonical("test-beneficiary"));
+    assert_eq!(config.fee_collector, canonical("test-fee-collector"));
+    assert_eq!(config.fee_rate, Decimal256::percent(20));
+    assert_eq!(config.moneymarket, canonical(MONEY_MARKET));
+    assert_eq!(config.stable_denom, "uusd".to_string());
+    assert_eq!(config.atoken, canonical(ATOKEN));
+    assert!(config.dp_token.is_empty());
+
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.contract, "crates.io:pylon-core-pool");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn instantiate_with_invalid_fee_rate() {
+    let mut deps = mock_dependencies(&[]);
+    mock_money_market(&mut deps, Decimal256::one());
+
+    let msg = InstantiateMsg {
+        fee_rate: Decimal256::percent(51),
+        ..instantiate_msg()
+    };
+    let err = contract::instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg)
+        .expect_err("testing: should reject fee rate above max");
+    assert_eq!(
+        err,
+        ContractError::InvalidFeeRate {
+            max: Decimal256::percent(50),
+            actual: Decimal256::percent(51),
+        }
+    );
+}
+
+#[test]
+fn instantiate_dp_token_options() {
+    let instantiate_with = |msg: InstantiateMsg| -> WasmMsg {
+        let mut deps = mock_dependencies(&[]);
+        mock_money_market(&mut deps, Decimal256::one());
+        let resp =
+            contract::instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        match resp.messages[0].msg.clone() {
+            CosmosMsg::Wasm(msg) => msg,
+            msg => panic!("testing: unexpected message {:?}", msg),
+        }
+    };
+    let token_msg = |name: &str, symbol: &str, marketing: Option<DpTokenMarketing>| {
+        to_binary(&dp_token::InstantiateMsg {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: MOCK_CONTRACT_ADDR.to_string(),
+                cap: None,
+            }),
+            marketing,
+        })
+        .unwrap()
+    };
+
+    // the pool administers its own DP token by default
+    let msg = instantiate_with(instantiate_msg());
+    assert_eq!(
+        msg,
+        WasmMsg::Instantiate {
+            admin: Some(MOCK_CONTRACT_ADDR.to_string()),
+            code_id: 666,
+            funds: vec![],
+            label: "Deposit Token - test-pool".to_string(),
+            msg: token_msg("Deposit Token - test-pool", "PylonDP", None),
+        }
+    );
+    if let WasmMsg::Instantiate { msg, .. } = msg {
+        // token contracts without marketing support must keep accepting it
+        assert!(!String::from_utf8(msg.to_vec())
+            .unwrap()
+            .contains("marketing"));
+    }
+
+    let marketing = DpTokenMarketing {
+        project: Some("https://pylon.money".to_string()),
+        description: Some("deposit token".to_string()),
+        marketing: Some("marketing".to_string()),
+        logo: Some(Logo::Url("https://pylon.money/logo.png".to_string())),
+    };
+    let msg = instantiate_with(InstantiateMsg {
+        dp_token_name: Some("Pylon Deposit".to_string()),
+        dp_token_symbol: Some("pDP".to_string()),
+        dp_token_marketing: Some(marketing.clone()),
+        dp_token_label: Some("pylon-dp".to_string()),
+        dp_token_admin: Some("admin".to_string()),
+        ..instantiate_msg()
+    });
+    assert_eq!(
+        msg,
+        WasmMsg::Instantiate {
+            admin: Some("admin".to_string()),
+            code_id: 666,
+            funds: vec![],
+            label: "pylon-dp".to_string(),
+            msg: token_msg("Pylon Deposit", "pDP", Some(marketing)),
+        }
+    );
+}
+
+#[test]
+fn migrate_dp_token() {
+    let mut deps = setup(Decimal256::one());
+    let msg = ExecuteMsg::MigrateDpToken {
+        code_id: 777,
+        msg: to_binary(&MigrateMsg {}).unwrap(),
+    };
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    let resp = contract::execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+    assert_eq!(
+        resp.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Migrate {
+            contract_addr: DP_TOKEN.to_string(),
+            new_code_id: 777,
+            msg: to_binary(&MigrateMsg {}).unwrap(),
+        })
+    );
+}
+
+#[test]
+fn adopt_dp_token() {
+    // existing DP token whose minter was handed to `minter`
+    let mock_existing_token = |deps: &mut MockDeps, minter: &'static str, total_supply: u128| {
+        deps.querier.register_wasm_smart_query_handler(
+            DP_TOKEN.to_string(),
+            Box::new(move |x| match from_binary::<Cw20QueryMsg>(x).unwrap() {
+                Cw20QueryMsg::Minter {} => to_binary(&Some(MinterResponse {
+                    minter: minter.to_string(),
+                    cap: None,
+                })),
+                Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                    name: "".to_string(),
+                    symbol: "".to_string(),
+                    decimals: 6,
+                    total_supply: Uint128::from(total_supply),
+                }),
+                _ => panic!("testing: unsupported token query"),
+            }),
+        );
+    };
+    let propose = |deps: &mut MockDeps| {
+        contract::instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            InstantiateMsg {
+                dp_code_id: None,
+                dp_token: Some(DP_TOKEN.to_string()),
+                ..instantiate_msg()
+            },
+        )
+        .unwrap()
+    };
+    let adopt = |deps: &mut MockDeps, sender: &str, funds: u128| {
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &coins(funds, "uusd")),
+            ExecuteMsg::AdoptDpToken {},
+        )
+    };
+    let new_deps = || {
+        let mut deps = mock_dependencies(&[]);
+        mock_money_market(&mut deps, Decimal256::from_str("1.25").unwrap());
+        mock_token(&mut deps, ATOKEN, 40, 0);
+        deps
+    };
+
+    // a fresh token needs a code id, backing UST only comes with the adoption
+    let mut deps = new_deps();
+    let err = contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            dp_code_id: None,
+            ..instantiate_msg()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DpCodeIdMissing {});
+    let err = contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &coins(50, "uusd")),
+        InstantiateMsg {
+            dp_token: Some(DP_TOKEN.to_string()),
+            ..instantiate_msg()
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AdoptionFundsNotExpected {});
+
+    // the pool stays unusable until the token is adopted
+    let mut deps = new_deps();
+    mock_existing_token(&mut deps, "old-pool", 100);
+    let resp = propose(&mut deps);
+    // no new token is instantiated
+    assert!(resp.messages.is_empty());
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DpTokenNotRegistered {});
+    let err = adopt(&mut deps, "owner", 50).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DpTokenMinterMismatch {
+            expected: MOCK_CONTRACT_ADDR.to_string(),
+            actual: "old-pool".to_string(),
+        }
+    );
+
+    // the token's admin migrated it, handing the minter to the pool
+    mock_existing_token(&mut deps, MOCK_CONTRACT_ADDR, 100);
+    let err = adopt(&mut deps, "someone", 50).unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+    // 40 aUST are worth 50 UST, so 50 UST more are needed to cover 100 DP
+    let err = adopt(&mut deps, "owner", 49).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MigratedAssetsShort {
+            dp_total_supply: Uint256::from(100u64),
+            pool_value_locked: Uint256::from(99u64),
+        }
+    );
+
+    let mut deps = new_deps();
+    mock_existing_token(&mut deps, MOCK_CONTRACT_ADDR, 100);
+    propose(&mut deps);
+    adopt(&mut deps, "owner", 50).unwrap();
+    let config: PoolConfigResponse =
+        from_binary(&contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Config {}).unwrap())
+            .unwrap();
+    assert_eq!(config.dp_token, DP_TOKEN.to_string());
+    assert_eq!(
+        crate::state::read(deps.as_ref().storage).unwrap().reserve,
+        Uint256::from(50u64)
+    );
+    // the adopted supply accrues time weight from now on
+    let later = mock_env().block.time.seconds() + 10;
+    assert_eq!(
+        crate::time_weight::total_accumulated(deps.as_ref().storage, later).unwrap(),
+        Uint256::from(1000u64)
+    );
+    let err = adopt(&mut deps, "owner", 0).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingDpToken {});
+}
+
+#[test]
+fn config_not_found() {
+    let mut deps = mock_dependencies(&[]);
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::ConfigNotFound {});
+
+    let err = contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Config {}).unwrap_err();
+    assert_eq!(err, ContractError::ConfigNotFound {});
+}
+
+#[test]
+fn dp_token_not_registered() {
+    let mut deps = mock_dependencies(&[]);
+    mock_money_market(&mut deps, Decimal256::one());
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        instantiate_msg(),
+    )
+    .unwrap();
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DpTokenNotRegistered {});
+
+    let err = contract::query(
+        deps.as_ref(),
+        mock_env(),
+        PoolQueryMsg::DepositAmountOf {
+            owner: "user".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DpTokenNotRegistered {});
+}
+
+#[test]
+fn invalid_address() {
+    let mut deps = setup(Decimal256::one());
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: Some("x".to_string()),
+            fee_collector: None,
+            fee_rate: None,
+            pauser: None,
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress {
+            address: "x".to_string()
+        }
+    );
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: Some("x".to_string()),
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress {
+            address: "x".to_string()
+        }
+    );
+}
+
+#[test]
+fn malformed_reply() {
+    let mut deps = mock_dependencies(&[]);
+    mock_money_market(&mut deps, Decimal256::one());
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        instantiate_msg(),
+    )
+    .unwrap();
+
+    let reply_with = |result: ContractResult<SubMsgExecutionResponse>| Reply { id: 1, result };
+    let with_data = |data: Option<Binary>| {
+        reply_with(ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data,
+        }))
+    };
+
+    let err = contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        reply_with(ContractResult::Err("out of gas".to_string())),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::MalformedReply {
+            id: 1,
+            reason: "out of gas".to_string(),
+        }
+    );
+
+    let err = contract::reply(deps.as_mut(), mock_env(), with_data(None)).unwrap_err();
+    assert!(matches!(err, ContractError::MalformedReply { id: 1, .. }));
+
+    // length prefix overruns the payload
+    let err = contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        with_data(Some(Binary::from(vec![0x0a, 0x05, b'x']))),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::MalformedReply { id: 1, .. }));
+
+    // well formed, but the contract address is not a valid address
+    let err = contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        with_data(Some(Binary::from(vec![0x0a, 0x01, b'x']))),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidAddress {
+            address: "x".to_string()
+        }
+    );
+
+    let err = contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 5,
+            result: ContractResult::Err("".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidReplyId { id: 5 });
+}
+
+#[test]
+fn configure_fee_rate() {
+    let mut deps = setup(Decimal256::one());
+
+    let msg = ExecuteMsg::Configure {
+        beneficiary: None,
+        fee_collector: None,
+        fee_rate: Some(Decimal256::percent(10)),
+        pauser: None,
+        deposit_cap: None,
+        deposit_cap_per_address: None,
+        beneficiaries: None,
+        redemption_policy: None,
+        reserve_ratio: None,
+        queue_redemptions: None,
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hacker", &[]),
+        msg.clone(),
+    )
+    .expect_err("testing: only owner can configure");
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg)
+        .expect("testing: should update fee rate");
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: Some(Decimal256::percent(60)),
+            pauser: None,
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .expect_err("testing: should reject fee rate above max");
+    assert!(matches!(err, ContractError::InvalidFeeRate { .. }));
+
+    let config: PoolConfigResponse =
+        from_binary(&contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Config {}).unwrap())
+            .unwrap();
+    assert_eq!(config.fee_rate, Decimal256::percent(10));
+}
+
+#[test]
+fn configure_reserve_ratio() {
+    let mut deps = setup(Decimal256::one());
+    let configure = |reserve_ratio: Decimal256| ExecuteMsg::Configure {
+        beneficiary: None,
+        fee_collector: None,
+        fee_rate: None,
+        pauser: None,
+        deposit_cap: None,
+        deposit_cap_per_address: None,
+        beneficiaries: None,
+        redemption_policy: None,
+        reserve_ratio: Some(reserve_ratio),
+        queue_redemptions: None,
+    };
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        configure(Decimal256::percent(51)),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidReserveRatio {
+            max: config::max_reserve_ratio(),
+            actual: Decimal256::percent(51),
+        }
+    );
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        configure(Decimal256::percent(10)),
+    )
+    .unwrap();
+
+    // 10% of the DP supply after minting stays in the pool
+    mock_token(&mut deps, DP_TOKEN, 0, 900);
+    let resp: SimulateDepositResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::SimulateDeposit {
+                amount: Uint256::from(100u64),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.dp_mint_amount, Uint256::from(100u64));
+    assert_eq!(resp.reserve_amount, Uint256::from(100u64));
+    assert_eq!(resp.atoken_amount, Uint256::zero());
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        ExecuteMsg::Rebalance {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    // refilling the empty reserve up to 90 redeems aUST, credited once it pays out
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Rebalance {},
+    )
+    .unwrap();
+    assert_eq!(resp.messages.last().unwrap().id, 3);
+    assert!(crate::state::read(deps.as_ref().storage)
+        .unwrap()
+        .reserve
+        .is_zero());
+
+    deps.querier
+        .with_balance(MOCK_CONTRACT_ADDR, &coins(88, "uusd"));
+    contract::reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 3,
+            result: ContractResult::Ok(SubMsgExecutionResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        crate::state::read(deps.as_ref().storage).unwrap().reserve,
+        Uint256::from(88u64)
+    );
+}
+
+#[test]
+fn claimable_reward_applies_fee_rate() {
+    let mut deps = setup(Decimal256::from_str("1.2").unwrap());
+    mock_token(&mut deps, ATOKEN, 1000, 1000);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: Some(Decimal256::percent(10)),
+            pauser: None,
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .unwrap();
+
+    // earnable = 1000 * 1.2 - 1000 = 200
+    let reward: ClaimableRewardResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::ClaimableReward {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(reward.fee, Uint256::from(20u64));
+    assert_eq!(reward.amount, Uint256::from(180u64));
+}
+
+#[test]
+fn deficit_blocks_earn() {
+    let mut deps = setup(Decimal256::one());
+    mock_token(&mut deps, ATOKEN, 900, 900);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    let solvency: SolvencyResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Solvency {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        solvency,
+        SolvencyResponse {
+            pool_value_locked: Uint256::from(900u64),
+            dp_total_supply: Uint256::from(1000u64),
+            deficit: Uint256::from(100u64),
+            redemption_policy: RedemptionPolicy::FirstCome,
+        }
+    );
+
+    let reward: ClaimableRewardResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::ClaimableReward {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(reward.amount, Uint256::zero());
+    assert_eq!(reward.fee, Uint256::zero());
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("test-beneficiary", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PoolInDeficit {
+            deficit: Uint256::from(100u64),
+        }
+    );
+
+    // first come redemptions are paid in full while the pool can afford them
+    let resp: SimulateRedeemResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::SimulateRedeem {
+                amount: Uint256::from(100u64),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(resp.stable_amount, Uint256::from(100u64));
+    assert_eq!(resp.haircut, Uint256::zero());
+}
+
+#[test]
+fn transfer_ownership() {
+    let mut deps = setup(Decimal256::one());
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hacker", &[]),
+        ExecuteMsg::TransferOwnership {
+            new_owner: "hacker".to_string(),
+        },
+    )
+    .expect_err("testing: only owner can transfer ownership");
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::TransferOwnership {
+            new_owner: "new-owner".to_string(),
+        },
+    )
+    .expect("testing: should start ownership transfer");
+
+    let config: PoolConfigResponse =
+        from_binary(&contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Config {}).unwrap())
+            .unwrap();
+    assert_eq!(config.owner, "owner".to_string());
+    assert_eq!(config.pending_owner, Some("new-owner".to_string()));
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hacker", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .expect_err("testing: only pending owner can accept");
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("new-owner", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .expect("testing: should accept ownership");
+
+    let config: PoolConfigResponse =
+        from_binary(&contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::Config {}).unwrap())
+            .unwrap();
+    assert_eq!(config.owner, "new-owner".to_string());
+    assert_eq!(config.pending_owner, None);
+
+    // previous owner lost its rights
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: Some(Decimal256::percent(10)),
+            pauser: None,
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .expect_err("testing: previous owner cannot configure");
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+}
+
+#[test]
+fn cancel_ownership_transfer() {
+    let mut deps = setup(Decimal256::one());
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::CancelOwnershipTransfer {},
+    )
+    .expect_err("testing: nothing to cancel");
+    assert_eq!(err, ContractError::NoPendingOwner {});
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::TransferOwnership {
+            new_owner: "new-owner".to_string(),
+        },
+    )
+    .unwrap();
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::CancelOwnershipTransfer {},
+    )
+    .expect("testing: should cancel ownership transfer");
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("new-owner", &[]),
+        ExecuteMsg::AcceptOwnership {},
+    )
+    .expect_err("testing: cancelled transfer cannot be accepted");
+    assert_eq!(err, ContractError::NoPendingOwner {});
+}
+
+#[test]
+fn pause() {
+    let mut deps = setup(Decimal256::one());
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: None,
+            pauser: Some("pauser".to_string()),
+            deposit_cap: None,
+            deposit_cap_per_address: None,
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .unwrap();
+
+    let pause_msg = ExecuteMsg::SetPause {
+        deposit: Some(true),
+        redeem: Some(true),
+        earn: Some(true),
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("hacker", &[]),
+        pause_msg.clone(),
+    )
+    .expect_err("testing: only owner or pauser can pause");
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pauser", &[]),
+        pause_msg,
+    )
+    .expect("testing: pauser should pause");
+
+    let pause: PauseInfoResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::PauseInfo {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        pause,
+        PauseInfoResponse {
+            deposit: true,
+            redeem: true,
+            earn: true,
+        }
+    );
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::DepositPaused {});
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "user".to_string(),
+            amount: Uint128::from(100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RedeemPaused {});
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("test-beneficiary", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EarnPaused {});
+
+    // owner lifts a single flag
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::SetPause {
+            deposit: None,
+            redeem: Some(false),
+            earn: None,
+        },
+    )
+    .expect("testing: owner should unpause");
+
+    let pause: PauseInfoResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::PauseInfo {}).unwrap(),
+    )
+    .unwrap();
+    assert!(pause.deposit && !pause.redeem && pause.earn);
+
+    // only the owner can take the pauser role away
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pauser", &[]),
+        ExecuteMsg::ClearPauser {},
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::ClearPauser {},
+    )
+    .expect("testing: owner should clear pauser");
+    assert_eq!(config::read(deps.as_ref().storage).unwrap().pauser, None);
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("pauser", &[]),
+        ExecuteMsg::SetPause {
+            deposit: Some(false),
+            redeem: None,
+            earn: None,
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized { .. }));
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::ClearPauser {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPauser {});
+}
+
+#[test]
+fn deposit_cap() {
+    let mut deps = setup(Decimal256::one());
+    mock_token(&mut deps, DP_TOKEN, 50, 900);
+
+    let capacity: DepositCapacityResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::DepositCapacityOf {
+                owner: "user".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(capacity.remaining, None);
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: None,
+            pauser: None,
+            deposit_cap: Some(Uint256::from(1000u64)),
+            deposit_cap_per_address: Some(Uint256::from(100u64)),
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .unwrap();
+
+    let capacity: DepositCapacityResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::DepositCapacityOf {
+                owner: "user".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        capacity,
+        DepositCapacityResponse {
+            total: Some(Uint256::from(100u64)),
+            address: Some(Uint256::from(50u64)),
+            remaining: Some(Uint256::from(50u64)),
+        }
+    );
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(60, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::DepositCapExceeded {
+            available: Uint256::from(50u64),
+            requested: Uint256::from(60u64),
+        }
+    );
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(50, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .expect("testing: deposit within cap should pass");
+
+    // zero lifts the per-address cap
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::Configure {
+            beneficiary: None,
+            fee_collector: None,
+            fee_rate: None,
+            pauser: None,
+            deposit_cap: None,
+            deposit_cap_per_address: Some(Uint256::zero()),
+            beneficiaries: None,
+            redemption_policy: None,
+            reserve_ratio: None,
+            queue_redemptions: None,
+        },
+    )
+    .unwrap();
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .expect("testing: deposit within total cap should pass");
+}
+
+#[test]
+fn earn_splits_reward_by_weight() {
+    let mut deps = setup(Decimal256::from_str("1.2").unwrap());
+    mock_token(&mut deps, ATOKEN, 1000, 1000);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    let configure_beneficiaries = |beneficiaries: Vec<Beneficiary>| ExecuteMsg::Configure {
+        beneficiary: None,
+        fee_collector: None,
+        fee_rate: None,
+        pauser: None,
+        deposit_cap: None,
+        deposit_cap_per_address: None,
+        beneficiaries: Some(beneficiaries),
+        redemption_policy: None,
+        reserve_ratio: None,
+        queue_redemptions: None,
+    };
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        configure_beneficiaries(vec![Beneficiary {
+            address: "team-a".to_string(),
+            weight: 0,
+        }]),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidBeneficiaries { .. }));
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        configure_beneficiaries(vec![
+            Beneficiary {
+                address: "team-a".to_string(),
+                weight: u64::MAX,
+            },
+            Beneficiary {
+                address: "team-b".to_string(),
+                weight: 1,
+            },
+        ]),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBeneficiaries {
+            reason: "total weight overflow".to_string(),
+        }
+    );
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        configure_beneficiaries(vec![
+            Beneficiary {
+                address: "team-a".to_string(),
+                weight: 1,
+            },
+            Beneficiary {
+                address: "team-b".to_string(),
+                weight: 2,
+            },
+        ]),
+    )
+    .unwrap();
+
+    // earnable = 200, fee = 40, amount = 160 => 53 / 106 / dust 1
+    let expected = vec![
+        BeneficiaryReward {
+            address: "team-a".to_string(),
+            amount: Uint256::from(53u64),
+        },
+        BeneficiaryReward {
+            address: "team-b".to_string(),
+            amount: Uint256::from(106u64),
+        },
+        BeneficiaryReward {
+            address: "test-beneficiary".to_string(),
+            amount: Uint256::from(1u64),
+        },
+    ];
+    let reward: ClaimableRewardResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::ClaimableReward {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(reward.amount, Uint256::from(160u64));
+    assert_eq!(reward.beneficiaries, expected);
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("test-beneficiary", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .unwrap();
+    let resp = distribute(&mut deps, mock_env(), 200).unwrap();
+    let transfers: Vec<(String, Uint128)> = resp
+        .messages
+        .iter()
+        .filter_map(|m| match &m.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                Some((to_address.clone(), amount[0].amount))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        transfers,
+        vec![
+            ("team-a".to_string(), Uint128::from(53u64)),
+            ("team-b".to_string(), Uint128::from(106u64)),
+            ("test-beneficiary".to_string(), Uint128::from(1u64)),
+            ("test-fee-collector".to_string(), Uint128::from(40u64)),
+        ]
+    );
+}
+
+#[test]
+fn permissionless_earn_with_bounty() {
+    let mut deps = setup(Decimal256::from_str("1.2").unwrap());
+    mock_token(&mut deps, ATOKEN, 1000, 1000);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    // without keeper mode only the beneficiaries earn, not even the owner
+    for caller in ["keeper", "owner"] {
+        let err = contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(caller, &[]),
+            ExecuteMsg::Earn {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+    }
+
+    let keeper = Keeper {
+        min_interval: 3600,
+        min_earnable: Uint256::from(1000u64),
+        bounty_rate: Decimal256::percent(11),
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::ConfigureKeeper {
+            keeper: Some(keeper.clone()),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidBountyRate { .. }));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecuteMsg::ConfigureKeeper {
+            keeper: Some(Keeper {
+                bounty_rate: Decimal256::percent(5),
+                ..keeper
+            }),
+        },
+    )
+    .unwrap();
+
+    // earnable = 200, fee = 40, bounty = 10 out of the 160 left for beneficiaries
+    let reward: ClaimableRewardResponse = from_binary(
+        &contract::query(deps.as_ref(), mock_env(), PoolQueryMsg::ClaimableReward {}).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(reward.bounty, Uint256::from(10u64));
+    assert_eq!(reward.amount, Uint256::from(160u64));
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .expect("testing: keeper can earn once interval passed");
+    let resp = distribute(&mut deps, mock_env(), 200).unwrap();
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Bank(BankMsg::Send {
+            to_address: "keeper".to_string(),
+            amount: coins(10, "uusd"),
+        })));
+
+    // neither interval passed nor enough earnable
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::EarnNotReady {
+            next_earn_at: mock_env().block.time.seconds() + 3600,
+            min_earnable: Uint256::from(1000u64),
+        }
+    );
+
+    // beneficiary and owner are never throttled and pay no bounty
+    for caller in ["test-beneficiary", "owner"] {
+        let resp = contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(caller, &[]),
+            ExecuteMsg::Earn {},
+        )
+        .expect("testing: beneficiary and owner can always earn");
+        assert!(resp
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "bounty" && attr.value == "0"));
+    }
+}
+
+#[test]
+fn earn_history() {
+    let mut deps = setup(Decimal256::from_str("1.2").unwrap());
+    mock_token(&mut deps, ATOKEN, 1000, 1000);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    for blocks in 1..=3 {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("test-beneficiary", &[]),
+            ExecuteMsg::Earn {},
+        )
+        .unwrap();
+        distribute(&mut deps, env, 200).unwrap();
+    }
+
+    let history = |start_after: Option<u64>, limit: Option<u32>| -> Vec<HarvestResponse> {
+        let resp: EarnHistoryResponse = from_binary(
+            &contract::query(
+                deps.as_ref(),
+                mock_env(),
+                PoolQueryMsg::EarnHistory { start_after, limit },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        resp.history
+    };
+
+    let all = history(None, None);
+    assert_eq!(
+        all.iter().map(|h| h.id).collect::<Vec<u64>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        all[0],
+        HarvestResponse {
+            id: 1,
+            height: mock_env().block.height + 1,
+            time: mock_env().block.time.seconds(),
+            exchange_rate: Decimal256::from_str("1.2").unwrap(),
+            earnable: Uint256::from(200u64),
+            amount: Uint256::from(160u64),
+            fee: Uint256::from(40u64),
+            bounty: Uint256::zero(),
+            caller: "test-beneficiary".to_string(),
+        }
+    );
+
+    let page = history(Some(1), Some(1));
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, 2);
+    assert!(history(Some(3), None).is_empty());
+}
+
+#[test]
+fn short_earn_payout_spares_the_reserve() {
+    let mut deps = setup(Decimal256::from_str("1.2").unwrap());
+    mock_token(&mut deps, ATOKEN, 1000, 1000);
+    mock_token(&mut deps, DP_TOKEN, 0, 1000);
+
+    let err = distribute(&mut deps, mock_env(), 0).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingClaim {});
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("test-beneficiary", &[]),
+        ExecuteMsg::Earn {},
+    )
+    .unwrap();
+
+    // earnable = 200, the market pays 190 => fee 38, amount 152
+    let resp = distribute(&mut deps, mock_env(), 190).unwrap();
+    assert_eq!(
+        resp.messages
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "test-beneficiary".to_string(),
+                amount: coins(152, "uusd"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "test-fee-collector".to_string(),
+                amount: coins(38, "uusd"),
+            }),
+        ]
+    );
+
+    let state = crate::state::read(deps.as_ref().storage).unwrap();
+    assert_eq!(state.reserve, Uint256::zero());
+    assert_eq!(state.total_yield_earned, Uint256::from(190u64));
+    assert_eq!(state.total_fee, Uint256::from(38u64));
+
+    let err = distribute(&mut deps, mock_env(), 0).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingClaim {});
+}
+
+#[test]
+fn deposit_to_recipient() {
+    let mut deps = setup(Decimal256::one());
+    mock_token(&mut deps, DP_TOKEN, 0, 0);
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("gateway", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: Some("user".to_string()),
+            min_mint: None,
+        },
+    )
+    .unwrap();
+
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: DP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: "user".to_string(),
+                amount: Uint128::from(100u64),
+            })
+            .unwrap(),
+            funds: vec![],
+        })));
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "sender" && a.value == "gateway"));
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "recipient" && a.value == "user"));
+}
+
+#[test]
+fn redeem_to_recipient() {
+    let mut deps = setup(Decimal256::one());
+    mock_token(&mut deps, DP_TOKEN, 0, 100);
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "vault".to_string(),
+            amount: Uint128::from(100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: Some("user".to_string()),
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "sender" && a.value == "vault"));
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "recipient" && a.value == "user"));
+
+    let resp = settle(&mut deps, 100).unwrap();
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: coins(100, "uusd"),
+        })));
+}
+
+#[test]
+fn time_weighted_deposit() {
+    let mut deps = setup(Decimal256::one());
+    mock_token_balances(&mut deps, DP_TOKEN, &[], 400);
+    let start = mock_env().block.time.seconds();
+    let env_at = |elapsed: u64| {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(elapsed);
+        env
+    };
+
+    for (elapsed, sender, amount) in [(0, "alice", 100), (10, "bob", 300)] {
+        contract::execute(
+            deps.as_mut(),
+            env_at(elapsed),
+            mock_info(sender, &coins(amount, "uusd")),
+            ExecuteMsg::Deposit {
+                recipient: None,
+                min_mint: None,
+            },
+        )
+        .unwrap();
+    }
+    mock_token_balances(&mut deps, DP_TOKEN, &[("bob", 300)], 400);
+    contract::execute(
+        deps.as_mut(),
+        env_at(20),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::from(100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    let resp: DepositBalanceSecondsResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            env_at(30),
+            PoolQueryMsg::DepositBalanceSecondsOf {
+                owner: "alice".to_string(),
+                at: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp,
+        DepositBalanceSecondsResponse {
+            amount: Uint256::from(2000u64),
+            total: Uint256::from(8000u64),
+        }
+    );
+
+    let share_of = |owner: &str, from: u64, to: u64| -> DepositShareResponse {
+        from_binary(
+            &contract::query(
+                deps.as_ref(),
+                env_at(30),
+                PoolQueryMsg::DepositShareOf {
+                    owner: owner.to_string(),
+                    start: start + from,
+                    end: start + to,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        share_of("alice", 0, 20),
+        DepositShareResponse {
+            amount: Uint256::from(2000u64),
+            total: Uint256::from(5000u64),
+            share: Decimal256::percent(40),
+        }
+    );
+    assert_eq!(share_of("alice", 20, 30).share, Decimal256::zero());
+    assert_eq!(share_of("bob", 20, 30).share, Decimal256::one());
+
+    let err = contract::query(
+        deps.as_ref(),
+        env_at(30),
+        PoolQueryMsg::DepositShareOf {
+            owner: "bob".to_string(),
+            start: start + 20,
+            end: start + 10,
+        },
+    )
+    .expect_err("testing: should reject inverted period");
+    assert_eq!(
+        err,
+        ContractError::InvalidSharePeriod {
+            start: start + 20,
+            end: start + 10,
+        }
+    );
+}
+
+#[test]
+fn time_weight_follows_transfers() {
+    let mut deps = setup(Decimal256::one());
+    let env_at = |elapsed: u64| {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(elapsed);
+        env
+    };
+    let balance_seconds_of = |deps: &MockDeps, owner: &str, elapsed: u64| {
+        let resp: DepositBalanceSecondsResponse = from_binary(
+            &contract::query(
+                deps.as_ref(),
+                env_at(elapsed),
+                PoolQueryMsg::DepositBalanceSecondsOf {
+                    owner: owner.to_string(),
+                    at: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        resp
+    };
+    let receive = |sender: &str, amount: u64, msg: Cw20HookMsg| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: sender.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&msg).unwrap(),
+        })
+    };
+
+    mock_token_balances(&mut deps, DP_TOKEN, &[], 0);
+    contract::execute(
+        deps.as_mut(),
+        env_at(0),
+        mock_info("alice", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap();
+
+    // routed through the pool, both sides are recorded
+    mock_token_balances(&mut deps, DP_TOKEN, &[("alice", 60)], 100);
+    let resp = contract::execute(
+        deps.as_mut(),
+        env_at(10),
+        mock_info(DP_TOKEN, &[]),
+        receive(
+            "alice",
+            40,
+            Cw20HookMsg::Transfer {
+                recipient: "bob".to_string(),
+            },
+        ),
+    )
+    .unwrap();
+    assert_eq!(
+        resp.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: DP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "bob".to_string(),
+                amount: Uint128::from(40u64),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    // transferred directly with a plain cw20 token, alice counts her last record until synced
+    mock_token_balances(&mut deps, DP_TOKEN, &[("bob", 40), ("carol", 60)], 100);
+    assert_eq!(
+        balance_seconds_of(&deps, "alice", 20).amount,
+        Uint256::from(1600u64)
+    );
+    assert_eq!(
+        balance_seconds_of(&deps, "carol", 20).amount,
+        Uint256::zero()
+    );
+
+    // anyone can sync both sides of the direct transfer
+    contract::execute(
+        deps.as_mut(),
+        env_at(20),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SyncBalances {
+            owners: vec!["alice".to_string(), "carol".to_string()],
+        },
+    )
+    .unwrap();
+
+    // carol redeems DP she never deposited
+    mock_token_balances(&mut deps, DP_TOKEN, &[("bob", 40)], 100);
+    contract::execute(
+        deps.as_mut(),
+        env_at(30),
+        mock_info(DP_TOKEN, &[]),
+        receive(
+            "carol",
+            60,
+            Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            },
+        ),
+    )
+    .unwrap();
+
+    // alice: 100 * 10 + 60 * 10, bob: 40 * 30, carol: 60 * 10
+    let alice = balance_seconds_of(&deps, "alice", 40);
+    let bob = balance_seconds_of(&deps, "bob", 40);
+    let carol = balance_seconds_of(&deps, "carol", 40);
+    assert_eq!(alice.amount, Uint256::from(1600u64));
+    assert_eq!(bob.amount, Uint256::from(1200u64));
+    assert_eq!(carol.amount, Uint256::from(600u64));
+    // 100 held for 30 seconds, then 40
+    assert_eq!(alice.total, Uint256::from(3400u64));
+    assert_eq!(alice.total, alice.amount + bob.amount + carol.amount);
+}
+
+#[test]
+fn deposit_snapshots() {
+    let mut deps = setup(Decimal256::one());
+    let start = mock_env().block.height;
+    let env_at = |blocks: u64| {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        env
+    };
+
+    mock_token(&mut deps, DP_TOKEN, 0, 0);
+    contract::execute(
+        deps.as_mut(),
+        env_at(1),
+        mock_info("alice", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap();
+    mock_token(&mut deps, DP_TOKEN, 0, 100);
+    contract::execute(
+        deps.as_mut(),
+        env_at(2),
+        mock_info("bob", &coins(50, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap();
+    // alice already sent her DP to the pool
+    mock_token(&mut deps, DP_TOKEN, 0, 150);
+    contract::execute(
+        deps.as_mut(),
+        env_at(3),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "alice".to_string(),
+            amount: Uint128::from(100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    // bob routes part of his DP through the pool, then moves the rest directly and syncs
+    mock_token_balances(&mut deps, DP_TOKEN, &[("bob", 30)], 50);
+    contract::execute(
+        deps.as_mut(),
+        env_at(4),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "bob".to_string(),
+            amount: Uint128::from(20u64),
+            msg: to_binary(&Cw20HookMsg::Transfer {
+                recipient: "carol".to_string(),
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    mock_token_balances(&mut deps, DP_TOKEN, &[("carol", 20), ("dave", 30)], 50);
+    contract::execute(
+        deps.as_mut(),
+        env_at(5),
+        mock_info("anyone", &[]),
+        ExecuteMsg::SyncBalances {
+            owners: vec!["bob".to_string(), "dave".to_string()],
+        },
+    )
+    .unwrap();
+
+    let deposit_amount_at = |owner: &str, blocks: u64| -> Uint256 {
+        let resp: DepositAmountResponse = from_binary(
+            &contract::query(
+                deps.as_ref(),
+                mock_env(),
+                PoolQueryMsg::DepositAmountAt {
+                    owner: owner.to_string(),
+                    height: start + blocks,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        resp.amount
+    };
+    assert_eq!(deposit_amount_at("alice", 0), Uint256::zero());
+    assert_eq!(deposit_amount_at("alice", 1), Uint256::from(100u64));
+    assert_eq!(deposit_amount_at("alice", 2), Uint256::from(100u64));
+    assert_eq!(deposit_amount_at("alice", 3), Uint256::zero());
+    assert_eq!(deposit_amount_at("bob", 1), Uint256::zero());
+    assert_eq!(deposit_amount_at("bob", 3), Uint256::from(50u64));
+    assert_eq!(deposit_amount_at("bob", 4), Uint256::from(30u64));
+    assert_eq!(deposit_amount_at("carol", 4), Uint256::from(20u64));
+    assert_eq!(deposit_amount_at("bob", 10), Uint256::zero());
+    assert_eq!(deposit_amount_at("dave", 4), Uint256::zero());
+    assert_eq!(deposit_amount_at("dave", 10), Uint256::from(30u64));
+
+    let total_deposit_amount_at = |blocks: u64| -> Uint256 {
+        let resp: TotalDepositAmountResponse = from_binary(
+            &contract::query(
+                deps.as_ref(),
+                mock_env(),
+                PoolQueryMsg::TotalDepositAmountAt {
+                    height: start + blocks,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        resp.amount
+    };
+    assert_eq!(total_deposit_amount_at(0), Uint256::zero());
+    assert_eq!(total_deposit_amount_at(2), Uint256::from(150u64));
+    assert_eq!(total_deposit_amount_at(10), Uint256::from(50u64));
+}
+
+#[test]
+fn simulate_deposit_and_redeem() {
+    let mut deps = setup(Decimal256::from_str("1.25").unwrap());
+    mock_token(&mut deps, DP_TOKEN, 0, 1_020_100);
+    deps.querier.with_tax(MockTax::new(
+        Decimal::percent(1),
+        &[("uusd", Uint128::from(1_000_000u64))],
+    ));
+
+    let resp: SimulateDepositResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::SimulateDeposit {
+                amount: Uint256::from(1_010_000u64),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp,
+        SimulateDepositResponse {
+            dp_mint_amount: Uint256::from(1_000_000u64),
+            tax: Uint256::from(10_000u64),
+            reserve_amount: Uint256::zero(),
+            atoken_amount: Uint256::from(800_000u64),
+        }
+    );
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(1_010_000, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap();
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: DP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: "user".to_string(),
+                amount: Uint128::from(1_000_000u64),
+            })
+            .unwrap(),
+            funds: vec![],
+        })));
+
+    let resp: SimulateRedeemResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            PoolQueryMsg::SimulateRedeem {
+                amount: Uint256::from(1_020_100u64),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        resp,
+        SimulateRedeemResponse {
+            stable_amount: Uint256::from(1_000_000u64),
+            tax: Uint256::from(20_100u64),
+            haircut: Uint256::zero(),
+            reserve_amount: Uint256::zero(),
+            atoken_amount: Uint256::from(816_080u64),
+        }
+    );
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "user".to_string(),
+            amount: Uint128::from(1_020_100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ATOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: MONEY_MARKET.to_string(),
+                amount: Uint128::from(816_080u64),
+                msg: to_binary(&AnchorHookMsg::RedeemStable {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })));
+
+    let resp = settle(&mut deps, 1_010_000).unwrap();
+    assert!(resp.messages.iter().any(|m| m.msg
+        == CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: coins(1_000_000, "uusd"),
+        })));
+}
+
+#[test]
+fn settle_redemption_tracks_dust() {
+    let mut deps = setup(Decimal256::one());
+    mock_token(&mut deps, DP_TOKEN, 0, 200);
+    deps.querier.with_tax(MockTax::new(
+        Decimal::percent(1),
+        &[("uusd", Uint128::from(1_000_000u64))],
+    ));
+
+    let err = settle(&mut deps, 0).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingSettlement {});
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "user".to_string(),
+            amount: Uint128::from(153u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+
+    // 150 arrived, 148 sent plus 1 tax, 1 is left over
+    let resp = settle(&mut deps, 150).unwrap();
+    assert_eq!(
+        resp.messages
+            .iter()
+            .map(|m| m.msg.clone())
+            .collect::<Vec<_>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: "user".to_string(),
+            amount: coins(148, "uusd"),
+        })]
+    );
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "dust" && a.value == "1"));
+    assert_eq!(
+        crate::state::read(deps.as_ref().storage).unwrap().dust,
+        Uint256::from(1u64)
+    );
+
+    let err = settle(&mut deps, 150).unwrap_err();
+    assert_eq!(err, ContractError::NoPendingSettlement {});
+}
+
+#[test]
+fn slippage_protection() {
+    let mut deps = setup(Decimal256::from_str("1.25").unwrap());
+    mock_token(&mut deps, DP_TOKEN, 0, 1_020_100);
+    deps.querier.with_tax(MockTax::new(
+        Decimal::percent(1),
+        &[("uusd", Uint128::from(1_000_000u64))],
+    ));
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(1_010_000, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: Some(Uint256::from(1_000_001u64)),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            minimum: Uint256::from(1_000_001u64),
+            actual: Uint256::from(1_000_000u64),
+        }
+    );
+
+    let redeem = |deps: &mut MockDeps, min_receive: u64| {
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(DP_TOKEN, &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "user".to_string(),
+                amount: Uint128::from(1_020_100u64),
+                msg: to_binary(&Cw20HookMsg::Redeem {
+                    recipient: None,
+                    min_receive: Some(Uint256::from(min_receive)),
+                })
+                .unwrap(),
+            }),
+        )
+    };
+    let err = redeem(&mut deps, 1_000_001).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            minimum: Uint256::from(1_000_001u64),
+            actual: Uint256::from(1_000_000u64),
+        }
+    );
+
+    // the simulation passes, but the money market paid less than expected
+    redeem(&mut deps, 1_000_000).unwrap();
+    let err = settle(&mut deps, 1_000_000).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SlippageExceeded {
+            minimum: Uint256::from(1_000_000u64),
+            actual: Uint256::from(990_099u64),
+        }
+    );
+}
+
+#[test]
+fn anchor_money_market_messages() {
+    let mut deps = setup(Decimal256::from_str("1.25").unwrap());
+    mock_token(&mut deps, DP_TOKEN, 0, 100);
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &coins(100, "uusd")),
+        ExecuteMsg::Deposit {
+            recipient: None,
+            min_mint: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        resp.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MONEY_MARKET.to_string(),
+            msg: to_binary(&AnchorHandleMsg::DepositStable {}).unwrap(),
+            funds: coins(100, "uusd"),
+        })
+    );
+
+    let resp = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DP_TOKEN, &[]),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "user".to_string(),
+            amount: Uint128::from(100u64),
+            msg: to_binary(&Cw20HookMsg::Redeem {
+                recipient: None,
+                min_receive: None,
+            })
+            .unwrap(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        resp.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ATOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: MONEY_MARKET.to_string(),
+                amount: Uint128::from(80u64),
+                msg: to_binary(&AnchorHookMsg::RedeemStable {}).unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn migrate_legacy_pool() {
+    let mut deps = mock_dependencies(&[]);
+    let api = deps.api;
+    let legacy = LegacyConfig {
+        this: api.addr_canonicalize("pool").unwrap(),
+        owner: api.addr_canonicalize("owner").unwrap(),
+        beneficiary: api.addr_canonicalize("test-beneficiary").unwrap(),
+        fee_collector: api.addr_canonicalize("test-fee-collector").unwrap(),
+        moneymarket: api.addr_canonicalize(MONEY_MARKET).unwrap(),
+        atoken: api.addr_canonicalize(ATOKEN).unwrap(),
+        stable_denom: "uusd".to_string(),
+        dp_token: api.addr_canonicalize(DP_TOKEN).unwrap(),
+    };
+    singleton(&mut deps.storage, config::CONFIG_KEY)
+        .save(&legacy)
+        .unwrap();
+    mock_token(&mut deps, DP_TOKEN, 0, 300);
+
+    contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {})
+        .expect("testing: should migrate legacy pool");
+
+    let config = config::read(&deps.storage).unwrap();
+    assert_eq!(config.owner, legacy.owner);
+    assert_eq!(config.dp_token, legacy.dp_token);
+    assert_eq!(config.fee_rate, Decimal256::percent(20));
+    assert_eq!(config.moneymarket_kind, MoneyMarketKind::Anchor);
+    assert_eq!(config.beneficiaries, vec![]);
+    assert_eq!(config.redemption_policy, RedemptionPolicy::FirstCome);
+    assert!(!config.queue_redemptions);
+    assert_eq!(
+        crate::state::read(&deps.storage).unwrap(),
+        crate::state::State::default()
+    );
+
+    let version = cw2::get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, "crates.io:pylon-core-pool");
+    assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn migrate_checks_version() {
+    let mut deps = setup(Decimal256::one());
+
+    contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {})
+        .expect("testing: should migrate to same version");
+
+    cw2::set_contract_version(&mut deps.storage, "crates.io:pylon-core-pool", "99.0.0").unwrap();
+    let err = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(err, ContractError::MigrationDowngrade { .. }));
+
+    cw2::set_contract_version(&mut deps.storage, "crates.io:other", "0.1.0").unwrap();
+    let err = contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::MigrationContractMismatch { .. }
+    ));
 }
@@ -0,0 +1,103 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_bignumber::Uint256;
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage};
+use cosmwasm_storage::{Bucket, ReadonlyBucket};
+use std::cmp::min;
+
+pub static PREFIX_TIME_WEIGHT: &[u8] = b"time_weight";
+pub static PREFIX_TOTAL_TIME_WEIGHT: &[u8] = b"total_time_weight";
+
+/// Deposit balance since `time` and balance-seconds accumulated before it
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Checkpoint {
+    pub time: u64,
+    pub balance: Uint256,
+    pub accumulated: Uint256,
+}
+
+impl Checkpoint {
+    /// checkpoint carried forward to `time`, which must not precede `self.time`
+    fn at(&self, time: u64) -> Checkpoint {
+        Checkpoint {
+            time,
+            balance: self.balance,
+            accumulated: self.accumulated + self.balance * Uint256::from(time - self.time),
+        }
+    }
+}
+
+fn user_namespace(owner: &CanonicalAddr) -> [&[u8]; 2] {
+    [PREFIX_TIME_WEIGHT, owner.as_slice()]
+}
+
+fn checkpoint_at(storage: &dyn Storage, namespaces: &[&[u8]], time: u64) -> StdResult<Checkpoint> {
+    let latest = ReadonlyBucket::<Checkpoint>::multilevel(storage, namespaces)
+        .range(
+            None,
+            Some(&time.saturating_add(1).to_be_bytes()),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+
+    Ok(match latest {
+        Some((_, checkpoint)) => checkpoint.at(time),
+        None => Checkpoint {
+            time,
+            ..Checkpoint::default()
+        },
+    })
+}
+
+fn store(
+    storage: &mut dyn Storage,
+    namespaces: &[&[u8]],
+    checkpoint: &Checkpoint,
+) -> StdResult<()> {
+    Bucket::multilevel(storage, namespaces).save(&checkpoint.time.to_be_bytes(), checkpoint)
+}
+
+/// records the DP balance `owner` holds from `time` on, as read from the DP token.
+/// holders are synced whenever DP moves through the pool, and by the DP token on its own transfers.
+pub fn update(
+    storage: &mut dyn Storage,
+    owner: &CanonicalAddr,
+    time: u64,
+    balance: Uint256,
+) -> StdResult<()> {
+    let namespaces = user_namespace(owner);
+    let mut checkpoint = checkpoint_at(storage, &namespaces, time)?;
+    checkpoint.balance = balance;
+    store(storage, &namespaces, &checkpoint)
+}
+
+/// records `amount` of DP minted, or returned to a holder, at `time`
+pub fn increase_total(storage: &mut dyn Storage, time: u64, amount: Uint256) -> StdResult<()> {
+    let mut total = checkpoint_at(storage, &[PREFIX_TOTAL_TIME_WEIGHT], time)?;
+    total.balance += amount;
+    store(storage, &[PREFIX_TOTAL_TIME_WEIGHT], &total)
+}
+
+/// records `amount` of DP handed to the pool for redemption at `time`.
+/// DP minted before time weights were tracked was never added, so the total bottoms out at zero.
+pub fn decrease_total(storage: &mut dyn Storage, time: u64, amount: Uint256) -> StdResult<()> {
+    let mut total = checkpoint_at(storage, &[PREFIX_TOTAL_TIME_WEIGHT], time)?;
+    total.balance = total.balance - min(total.balance, amount);
+    store(storage, &[PREFIX_TOTAL_TIME_WEIGHT], &total)
+}
+
+/// balance-seconds `owner` accumulated until `time`, as recorded by `update`
+pub fn accumulated_of(
+    storage: &dyn Storage,
+    owner: &CanonicalAddr,
+    time: u64,
+) -> StdResult<Uint256> {
+    Ok(checkpoint_at(storage, &user_namespace(owner), time)?.accumulated)
+}
+
+/// balance-seconds of all DP held outside the pool, accumulated until `time`
+pub fn total_accumulated(storage: &dyn Storage, time: u64) -> StdResult<Uint256> {
+    Ok(checkpoint_at(storage, &[PREFIX_TOTAL_TIME_WEIGHT], time)?.accumulated)
+}
@@ -0,0 +1,25 @@
+use cosmwasm_std::{Addr, Api, CanonicalAddr};
+
+/// address the api refused, each contract turns it into its own error
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidAddress {
+    pub address: String,
+}
+
+pub fn validate(api: &dyn Api, address: &str) -> Result<Addr, InvalidAddress> {
+    api.addr_validate(address).map_err(|_| InvalidAddress {
+        address: address.to_string(),
+    })
+}
+
+pub fn canonicalize(api: &dyn Api, address: &str) -> Result<CanonicalAddr, InvalidAddress> {
+    api.addr_canonicalize(address).map_err(|_| InvalidAddress {
+        address: address.to_string(),
+    })
+}
+
+pub fn humanize(api: &dyn Api, address: &CanonicalAddr) -> Result<Addr, InvalidAddress> {
+    api.addr_humanize(address).map_err(|_| InvalidAddress {
+        address: address.to_string(),
+    })
+}
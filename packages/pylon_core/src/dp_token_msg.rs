@@ -0,0 +1,9 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Moves a plain cw20 DP token, or an older DP token, onto the DP token contract.
+/// Instantiate, execute and query messages are the ones of cw20-base.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {
+    pub minter: Option<String>, // hands minting over to another pool, keeps the current minter if None
+}
@@ -0,0 +1,58 @@
+use cosmwasm_bignumber::Decimal256;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::pool_msg::MoneyMarketKind;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub pool_code_id: u64,
+    pub dp_code_id: u64,
+    pub fee_collector: String,
+    pub fee_rate: Decimal256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Configure {
+        pool_code_id: Option<u64>,
+        dp_code_id: Option<u64>,
+        fee_collector: Option<String>,
+        fee_rate: Option<Decimal256>,
+    },
+    CreatePool {
+        pool_name: String,
+        beneficiary: String,
+        moneymarket: String,
+        moneymarket_kind: MoneyMarketKind,
+        fee_collector: Option<String>, // defaults to the factory's fee collector
+        fee_rate: Option<Decimal256>,  // defaults to the factory's fee rate
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Correspond response struct is [ConfigResponse](crate::factory_resp::ConfigResponse)
+    Config {},
+    /// Correspond response struct is [PoolsResponse](crate::factory_resp::PoolsResponse)
+    Pools {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Correspond response struct is [PoolResponse](crate::factory_resp::PoolResponse)
+    PoolByName { name: String },
+    /// Correspond response struct is [PoolResponse](crate::factory_resp::PoolResponse)
+    PoolByDpToken { dp_token: String },
+    /// Correspond response struct is [PoolsResponse](crate::factory_resp::PoolsResponse)
+    /// Pools are indexed under the beneficiary they were created with, a later change on the pool is not reflected
+    PoolsByBeneficiary {
+        beneficiary: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
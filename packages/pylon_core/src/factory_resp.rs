@@ -0,0 +1,28 @@
+use cosmwasm_bignumber::Decimal256;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    pub pool_code_id: u64,
+    pub dp_code_id: u64,
+    pub fee_collector: String,
+    pub fee_rate: Decimal256,
+}
+
+/// Pool created by the factory. `beneficiary` is the one the pool was created with
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    pub id: u64,
+    pub address: String,
+    pub name: String,
+    pub dp_token: String,
+    pub beneficiary: String,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolsResponse {
+    pub pools: Vec<PoolResponse>,
+}
@@ -1,2 +1,8 @@
+pub mod address;
+pub mod dp_token_msg;
+pub mod factory_msg;
+pub mod factory_resp;
 pub mod pool_msg;
 pub mod pool_resp;
+#[allow(renamed_and_removed_lints, unused_parens, mismatched_lifetime_syntaxes)]
+pub mod response;
@@ -1,32 +1,122 @@
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_bignumber::{Decimal256, Uint256};
+use cosmwasm_std::Binary;
+use cw20::{Cw20ReceiveMsg, Logo};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MoneyMarketKind {
+    Anchor,
+}
+
+/// How redemptions share the loss once the pool value falls below DP supply
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RedemptionPolicy {
+    #[default]
+    FirstCome, // DP is redeemed 1:1 as long as the pool can pay for it
+    ProRata, // every redemption takes a haircut of pool value / DP supply
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub pool_name: String,
     pub beneficiary: String,
     pub fee_collector: String,
+    pub fee_rate: Decimal256,
     pub moneymarket: String,
-    pub dp_code_id: u64,
+    pub moneymarket_kind: MoneyMarketKind,
+    pub dp_code_id: Option<u64>,  // required unless `dp_token` is given
+    pub dp_token: Option<String>, // existing DP token to adopt instead of instantiating one
+    pub dp_token_name: Option<String>, // defaults to "Deposit Token - {pool_name}"
+    pub dp_token_symbol: Option<String>, // defaults to "PylonDP"
+    pub dp_token_marketing: Option<DpTokenMarketing>,
+    pub dp_token_label: Option<String>, // defaults to the token name
+    pub dp_token_admin: Option<String>, // wasm admin of the token, defaults to the pool
+}
+
+/// Marketing info of the DP token, as accepted by cw20-base
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DpTokenMarketing {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Beneficiary {
+    pub address: String,
+    pub weight: u64,
+}
+
+/// Opens earn to anyone once `min_interval` seconds passed since the last earn
+/// or the earnable amount reached `min_earnable`. The caller receives `bounty_rate` of it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Keeper {
+    pub min_interval: u64,
+    pub min_earnable: Uint256,
+    pub bounty_rate: Decimal256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
-    Deposit {}, // UST -> DP (user)
-    Earn {},    // x -> UST (beneficiary)
+    Deposit {
+        recipient: Option<String>,
+        min_mint: Option<Uint256>, // fails if fewer DP would be minted
+    }, // UST -> DP (user, or recipient)
+    Earn {}, // x -> UST (owner or beneficiaries, or anyone in keeper mode)
     Configure {
         beneficiary: Option<String>,
         fee_collector: Option<String>,
+        fee_rate: Option<Decimal256>,
+        pauser: Option<String>,
+        deposit_cap: Option<Uint256>,             // 0 = no cap
+        deposit_cap_per_address: Option<Uint256>, // 0 = no cap
+        beneficiaries: Option<Vec<Beneficiary>>,  // [] = all to beneficiary
+        redemption_policy: Option<RedemptionPolicy>,
+        reserve_ratio: Option<Decimal256>, // 0 = everything goes to the money market
+        queue_redemptions: Option<bool>,   // queue redemptions the money market cannot pay
     },
+    ConfigureKeeper {
+        keeper: Option<Keeper>,
+    }, // None = only the beneficiaries can earn
+    SetPause {
+        deposit: Option<bool>,
+        redeem: Option<bool>,
+        earn: Option<bool>,
+    }, // owner or pauser
+    ClearPauser {}, // only the owner can pause afterwards
+    TransferOwnership {
+        new_owner: String,
+    }, // owner -> pending owner
+    AcceptOwnership {}, // pending owner -> owner
+    CancelOwnershipTransfer {},
+    Rebalance {},          // moves the UST reserve back to its target ratio (owner)
+    ProcessRedemptions {}, // fills queued redemptions in order (anyone)
+    SyncBalances {
+        owners: Vec<String>,
+    }, // re-reads DP balances for time weights and snapshots (anyone)
+    AdoptDpToken {}, // takes over the `dp_token` given at instantiation once its minter is the pool (owner)
+    MigrateDpToken {
+        code_id: u64,
+        msg: Binary,
+    }, // while the pool is the DP token's wasm admin (owner)
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Redeem {},
+    Redeem {
+        recipient: Option<String>,
+        min_receive: Option<Uint256>, // fails if less UST would be paid out
+    }, // DP -> UST (sender, or recipient)
+    Transfer {
+        recipient: String,
+    }, // DP -> DP of recipient, keeping time weights and snapshots of both in sync
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -40,7 +130,41 @@ pub enum QueryMsg {
     Config {}, // -> Config
     /// Correspond response struct is [ClaimableRewardResponse](crate::pool_resp::ClaimableRewardResponse)
     ClaimableReward {}, // -> Uint128
+    /// Correspond response struct is [PauseInfoResponse](crate::pool_resp::PauseInfoResponse)
+    PauseInfo {},
+    /// Correspond response struct is [DepositCapacityResponse](crate::pool_resp::DepositCapacityResponse)
+    DepositCapacityOf { owner: String },
+    /// Correspond response struct is [DepositBalanceSecondsResponse](crate::pool_resp::DepositBalanceSecondsResponse)
+    DepositBalanceSecondsOf { owner: String, at: Option<u64> }, // defaults to current block time
+    /// Correspond response struct is [DepositShareResponse](crate::pool_resp::DepositShareResponse)
+    DepositShareOf { owner: String, start: u64, end: u64 },
+    /// Correspond response struct is [DepositAmountResponse](crate::pool_resp::DepositAmountResponse)
+    DepositAmountAt { owner: String, height: u64 },
+    /// Correspond response struct is [TotalDepositAmountResponse](crate::pool_resp::TotalDepositAmountResponse)
+    TotalDepositAmountAt { height: u64 },
+    /// Correspond response struct is [PoolInfoResponse](crate::pool_resp::PoolInfoResponse)
+    PoolInfo {},
+    /// Correspond response struct is [SimulateDepositResponse](crate::pool_resp::SimulateDepositResponse)
+    SimulateDeposit { amount: Uint256 }, // stable coins to deposit
+    /// Correspond response struct is [SimulateRedeemResponse](crate::pool_resp::SimulateRedeemResponse)
+    SimulateRedeem { amount: Uint256 }, // DP to redeem
+    /// Correspond response struct is [EarnHistoryResponse](crate::pool_resp::EarnHistoryResponse)
+    EarnHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Correspond response struct is [SolvencyResponse](crate::pool_resp::SolvencyResponse)
+    Solvency {},
+    /// Correspond response struct is [RedemptionTicketsResponse](crate::pool_resp::RedemptionTicketsResponse)
+    RedemptionTicketsOf {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Correspond response struct is [RedemptionQueueResponse](crate::pool_resp::RedemptionQueueResponse)
+    RedemptionQueue {},
 }
-/// We currently take no arguments for migrations
+/// We currently take no arguments for migrations.
+/// New config fields are filled with defaults while migrating.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -1,7 +1,9 @@
-use cosmwasm_bignumber::Uint256;
+use cosmwasm_bignumber::{Decimal256, Uint256};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::pool_msg::{Beneficiary, Keeper, MoneyMarketKind, RedemptionPolicy};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DepositAmountResponse {
     pub amount: Uint256,
@@ -14,16 +16,154 @@ pub struct TotalDepositAmountResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
+    pub owner: String,
+    pub pending_owner: Option<String>,
+    pub pauser: Option<String>,
     pub beneficiary: String,
+    pub beneficiaries: Vec<Beneficiary>,
     pub fee_collector: String,
+    pub fee_rate: Decimal256,
+    pub keeper: Option<Keeper>,
     pub moneymarket: String,
+    pub moneymarket_kind: MoneyMarketKind,
     pub stable_denom: String,
     pub anchor_token: String,
     pub dp_token: String,
+    pub deposit_cap: Option<Uint256>,
+    pub deposit_cap_per_address: Option<Uint256>,
+    pub redemption_policy: RedemptionPolicy,
+    pub reserve_ratio: Decimal256,
+    pub queue_redemptions: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiaryReward {
+    pub address: String,
+    pub amount: Uint256,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ClaimableRewardResponse {
+    /// paid to the beneficiaries when the owner or a beneficiary calls earn
     pub amount: Uint256,
     pub fee: Uint256,
+    /// only paid in keeper mode, to an earn caller who is neither the owner nor a beneficiary.
+    /// it is then taken out of `amount`
+    pub bounty: Uint256,
+    pub beneficiaries: Vec<BeneficiaryReward>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfoResponse {
+    pub deposit: bool,
+    pub redeem: bool,
+    pub earn: bool,
+}
+
+/// Remaining DP amounts which can still be minted, `None` means unlimited
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositCapacityResponse {
+    pub total: Option<Uint256>,
+    pub address: Option<Uint256>,
+    pub remaining: Option<Uint256>,
+}
+
+/// Time-weighted deposit, in DP amount multiplied by seconds held
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositBalanceSecondsResponse {
+    pub amount: Uint256,
+    pub total: Uint256,
+}
+
+/// Time-weighted deposit accumulated between `start` and `end`, and its share of the pool
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositShareResponse {
+    pub amount: Uint256,
+    pub total: Uint256,
+    pub share: Decimal256,
+}
+
+/// Current holdings of the pool and lifetime totals since the counters were introduced
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolInfoResponse {
+    pub atoken_balance: Uint256,
+    pub reserve: Uint256,
+    pub dust: Uint256,
+    pub exchange_rate: Decimal256,
+    pub pool_value_locked: Uint256,
+    pub dp_total_supply: Uint256,
+    pub total_deposited: Uint256,
+    pub total_redeemed: Uint256,
+    pub total_yield_earned: Uint256,
+    pub total_fee: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateDepositResponse {
+    pub dp_mint_amount: Uint256,
+    pub tax: Uint256,
+    pub reserve_amount: Uint256,
+    pub atoken_amount: Uint256,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateRedeemResponse {
+    pub stable_amount: Uint256,
+    pub tax: Uint256,
+    pub haircut: Uint256,
+    pub reserve_amount: Uint256,
+    pub atoken_amount: Uint256,
+}
+
+/// Single `earn` call. `amount` went to the beneficiaries, `fee` to the fee collector
+/// and `bounty` to the caller, all out of `earnable`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HarvestResponse {
+    pub id: u64,
+    pub height: u64,
+    pub time: u64,
+    pub exchange_rate: Decimal256,
+    pub earnable: Uint256,
+    pub amount: Uint256,
+    pub fee: Uint256,
+    pub bounty: Uint256,
+    pub caller: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EarnHistoryResponse {
+    pub history: Vec<HarvestResponse>,
+}
+
+/// `deficit` is the DP supply not backed by the pool value, zero while the pool is solvent
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolvencyResponse {
+    pub pool_value_locked: Uint256,
+    pub dp_total_supply: Uint256,
+    pub deficit: Uint256,
+    pub redemption_policy: RedemptionPolicy,
+}
+
+/// DP locked in the pool until the money market can pay `recipient` for it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionTicketResponse {
+    pub id: u64,
+    pub owner: String,
+    pub recipient: String,
+    pub amount: Uint256,
+    pub min_receive: Uint256,
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionTicketsResponse {
+    pub tickets: Vec<RedemptionTicketResponse>,
+}
+
+/// `next_ticket` is the id filled first by `process_redemptions`, if any
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RedemptionQueueResponse {
+    pub depth: u64,
+    pub pending_amount: Uint256,
+    pub next_ticket: Option<u64>,
 }
@@ -1,10 +1,10 @@
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_slice, Binary, Coin, ContractResult, OwnedDeps, Querier, QuerierResult, QueryRequest,
-    StdResult, SystemError, SystemResult, WasmQuery,
+    from_slice, to_binary, Binary, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
-use terra_cosmwasm::TerraQueryWrapper;
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
 
 #[allow(dead_code)]
 pub fn mock_dependencies(
@@ -17,16 +17,36 @@ pub fn mock_dependencies(
             base: MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]),
             wasm_smart_query_handlers: HashMap::new(),
             wasm_raw_query_handlers: HashMap::new(),
+            tax: MockTax::default(),
         },
     }
 }
 
 pub type WasmQueryHandler = dyn Fn(&Binary) -> StdResult<Binary>;
 
+#[derive(Clone, Default)]
+pub struct MockTax {
+    rate: Decimal,
+    caps: HashMap<String, Uint128>,
+}
+
+impl MockTax {
+    pub fn new(rate: Decimal, caps: &[(&str, Uint128)]) -> Self {
+        MockTax {
+            rate,
+            caps: caps
+                .iter()
+                .map(|(denom, cap)| (denom.to_string(), *cap))
+                .collect(),
+        }
+    }
+}
+
 pub struct CustomMockWasmQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     wasm_smart_query_handlers: HashMap<String, Box<WasmQueryHandler>>,
     wasm_raw_query_handlers: HashMap<String, Box<WasmQueryHandler>>,
+    tax: MockTax,
 }
 
 impl Querier for CustomMockWasmQuerier {
@@ -46,6 +66,16 @@ impl Querier for CustomMockWasmQuerier {
 }
 
 impl CustomMockWasmQuerier {
+    #[allow(dead_code)]
+    pub fn with_tax(&mut self, tax: MockTax) {
+        self.tax = tax;
+    }
+
+    #[allow(dead_code)]
+    pub fn with_balance(&mut self, address: &str, balance: &[Coin]) {
+        self.base.update_balance(address, balance.to_vec());
+    }
+
     #[allow(dead_code)]
     pub fn register_wasm_smart_query_handler(
         &mut self,
@@ -83,6 +113,26 @@ impl CustomMockWasmQuerier {
                     kind: stringify!(request).to_string(),
                 }),
             },
+            QueryRequest::Custom(TerraQueryWrapper {
+                route: TerraRoute::Treasury,
+                query_data,
+            }) => match query_data {
+                TerraQuery::TaxRate {} => SystemResult::Ok(
+                    to_binary(&TaxRateResponse {
+                        rate: self.tax.rate,
+                    })
+                    .into(),
+                ),
+                TerraQuery::TaxCap { denom } => SystemResult::Ok(
+                    to_binary(&TaxCapResponse {
+                        cap: self.tax.caps.get(denom).copied().unwrap_or_default(),
+                    })
+                    .into(),
+                ),
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: stringify!(query_data).to_string(),
+                }),
+            },
             _ => self.base.handle_query(request),
         }
     }